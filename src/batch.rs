@@ -0,0 +1,252 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::Line;
+use std::collections::HashMap;
+
+/// A struct representing the opening of an IRCv3 batch
+/// (`BATCH +reference type params...`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Batch {
+    /// The batch's reference tag, without the leading `+`.
+    pub reference: String,
+
+    /// The batch's type, such as `netsplit` or `chathistory`.
+    pub kind: String,
+
+    /// Any params given for the batch's type. This will be an empty
+    /// vector if there are none.
+    pub params: Vec<String>,
+}
+
+impl Batch {
+    /// Parses the `BATCH` command that opens a batch.
+    ///
+    /// # Arguments
+    /// - `line` - The line to parse.
+    ///
+    /// # Returns
+    /// - [`Option<Batch>`] - The batch, or [`None`] if this line does not
+    ///   open a batch.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":irc.example.com BATCH +abc netsplit irc.hub other.host\r\n").unwrap().pop_front().unwrap();
+    /// let batch = ircparser::Batch::from_line(&line).unwrap();
+    ///
+    /// assert_eq!(batch.reference, "abc");
+    /// assert_eq!(batch.kind, "netsplit");
+    /// assert_eq!(batch.params, vec!["irc.hub", "other.host"]);
+    /// ```
+    ///
+    pub fn from_line(line: &Line) -> Option<Self> {
+        if !line.command.eq_ignore_ascii_case("BATCH") || line.params.len() < 2 {
+            return None;
+        }
+
+        let reference = line.params[0].strip_prefix('+')?;
+        Some(Self {
+            reference: reference.to_string(),
+            kind: line.params[1].clone(),
+            params: line.params[2..].to_vec(),
+        })
+    }
+
+    /// Checks whether this is a `chathistory` batch, which holds
+    /// messages replayed by the IRCv3 chathistory extension.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this is a `chathistory` batch.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":irc.example.com BATCH +abc chathistory #rickastley\r\n").unwrap().pop_front().unwrap();
+    /// let batch = ircparser::Batch::from_line(&line).unwrap();
+    ///
+    /// assert!(batch.is_chathistory());
+    /// assert_eq!(batch.chathistory_target(), Some("#rickastley"));
+    /// ```
+    ///
+    pub fn is_chathistory(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("chathistory")
+    }
+
+    /// Gets the target (the channel or nick) whose history a
+    /// `chathistory` batch holds.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The target, or [`None`] if this is not a
+    ///   `chathistory` batch.
+    ///
+    pub fn chathistory_target(&self) -> Option<&str> {
+        if !self.is_chathistory() {
+            return None;
+        }
+
+        self.params.first().map(|x| x.as_str())
+    }
+
+    /// Wraps lines in a batch, for sending. The lines are preceded by a
+    /// `BATCH +reference` command and followed by a `BATCH -reference`
+    /// command, and each is given a `batch` tag referencing the batch.
+    /// Lines which already have a `batch` tag (such as those inside a
+    /// nested batch) keep it.
+    ///
+    /// # Arguments
+    /// - `reference` - The batch's reference tag, without the leading
+    ///   `+`.
+    /// - `kind` - The batch's type.
+    /// - `params` - Any params for the batch's type.
+    /// - `lines` - The lines to wrap.
+    ///
+    /// # Returns
+    /// - [`Vec<Line>`] - The opening command, the tagged lines, and the
+    ///   closing command, in order.
+    ///
+    /// # Example
+    /// ```
+    /// let lines = vec![ircparser::Line::from(("PRIVMSG", vec!["#rickastley", "Never gonna give you up!"]))];
+    /// let wrapped = ircparser::Batch::wrap("abc", "example.com/lyrics", &[], &lines);
+    ///
+    /// let wrapped: Vec<String> = wrapped.iter().map(|x| x.to_string()).collect();
+    /// assert_eq!(
+    ///     wrapped,
+    ///     vec![
+    ///         "BATCH +abc example.com/lyrics",
+    ///         "@batch=abc PRIVMSG #rickastley :Never gonna give you up!",
+    ///         "BATCH -abc",
+    ///     ]
+    /// );
+    /// ```
+    ///
+    pub fn wrap(reference: &str, kind: &str, params: &[&str], lines: &[Line]) -> Vec<Line> {
+        let mut start = vec![format!("+{reference}"), kind.to_string()];
+        start.extend(params.iter().map(|x| x.to_string()));
+
+        let mut wrapped = Vec::with_capacity(lines.len() + 2);
+        wrapped.push(Line::new(HashMap::new(), None, "BATCH", start));
+        wrapped.extend(lines.iter().map(|x| {
+            let mut line = x.clone();
+            if !line.tags.contains_key("batch") {
+                line.set_tag("batch", reference);
+            }
+            line
+        }));
+        wrapped.push(Line::new(
+            HashMap::new(),
+            None,
+            "BATCH",
+            vec![format!("-{reference}")],
+        ));
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod test_batch {
+    use super::Batch;
+    use crate::Line;
+
+    #[test]
+    fn test_from_line() {
+        let line = Line::from(("BATCH", vec!["+abc", "chathistory", "#rickastley"]));
+
+        assert_eq!(
+            Batch::from_line(&line),
+            Some(Batch {
+                reference: "abc".to_string(),
+                kind: "chathistory".to_string(),
+                params: vec!["#rickastley".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_line_end() {
+        assert_eq!(Batch::from_line(&Line::from(("BATCH", vec!["-abc"]))), None);
+        assert_eq!(
+            Batch::from_line(&Line::from(("PING", vec!["+abc", "x"]))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        let lines = vec![
+            Line::from(("PRIVMSG", vec!["#rickastley", "Never gonna give you up!"])),
+            Line::from(("PRIVMSG", vec!["#rickastley", "Never gonna let you down!"])),
+        ];
+        let wrapped = Batch::wrap("abc", "draft/multiline", &["#rickastley"], &lines);
+
+        assert_eq!(wrapped.len(), 4);
+        assert_eq!(
+            wrapped[0].to_string(),
+            "BATCH +abc draft/multiline #rickastley"
+        );
+        for line in &wrapped[1..3] {
+            assert_eq!(line.tags["batch"], "abc");
+            assert_eq!(line.command, "PRIVMSG");
+        }
+        assert_eq!(wrapped[3].to_string(), "BATCH -abc");
+    }
+
+    #[test]
+    fn test_wrap_nested() {
+        let inner = Batch::wrap(
+            "inner",
+            "netsplit",
+            &["irc.hub", "other.host"],
+            &[Line::from(("QUIT", vec!["irc.hub other.host"]))],
+        );
+        let outer = Batch::wrap("outer", "example.com/nested", &[], &inner);
+
+        assert_eq!(outer[1].tags["batch"], "outer");
+        assert_eq!(outer[2].tags["batch"], "inner");
+        assert_eq!(outer[3].tags["batch"], "outer");
+    }
+
+    #[test]
+    fn test_chathistory() {
+        let line = Line::from(("BATCH", vec!["+abc", "chathistory", "#rickastley"]));
+        let batch = Batch::from_line(&line).unwrap();
+
+        assert!(batch.is_chathistory());
+        assert_eq!(batch.chathistory_target(), Some("#rickastley"));
+    }
+
+    #[test]
+    fn test_not_chathistory() {
+        let line = Line::from(("BATCH", vec!["+abc", "netsplit", "irc.hub", "other.host"]));
+        let batch = Batch::from_line(&line).unwrap();
+
+        assert!(!batch.is_chathistory());
+        assert_eq!(batch.chathistory_target(), None);
+    }
+}
@@ -0,0 +1,84 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::parse;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Measures how long it takes to parse a set of sample messages. Each
+/// sample is parsed once per iteration, and lines that fail to parse
+/// are counted the same as those that don't. This allows parsing
+/// throughput to be measured against real traffic.
+///
+/// This requires the `bench` feature.
+///
+/// # Arguments
+/// - `samples` - The messages to parse. Each can comprise of multiple
+///   lines, as with [`parse`].
+/// - `iterations` - The number of times to parse every sample.
+///
+/// # Returns
+/// - [`Duration`] - The total time taken.
+///
+/// # Example
+/// ```
+/// let samples = ["PING :irc.example.com\r\n", "PRIVMSG #rickastley :Never gonna give you up!\r\n"];
+/// let elapsed = ircparser::bench_parse(&samples, 100);
+///
+/// println!("{:?} per parse", elapsed / 200);
+/// ```
+///
+pub fn bench_parse(samples: &[&str], iterations: usize) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..iterations {
+        for sample in samples {
+            let _ = black_box(parse(black_box(sample)));
+        }
+    }
+
+    start.elapsed()
+}
+
+#[cfg(test)]
+mod test_bench {
+    use super::bench_parse;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bench_parse() {
+        let samples = [
+            "@id=123 :nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n",
+            "PING :irc.example.com\r\n",
+        ];
+
+        assert!(bench_parse(&samples, 10) > Duration::ZERO);
+    }
+}
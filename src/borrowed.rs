@@ -0,0 +1,52 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// A line that borrows its components from the text it was parsed from,
+/// as returned by [ircparser::parse_borrowed_into](super::parse_borrowed_into).
+///
+/// Unlike a [`Line`](super::Line), tags are stored in the order they
+/// were sent, and their values are left escaped, as unescaping them
+/// would require allocating.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BorrowedLine<'a> {
+    /// This line's tags, as key-value pairs. Values are still escaped,
+    /// and tags without a value have an empty one.
+    pub tags: Vec<(&'a str, &'a str)>,
+
+    /// This line's source, including the leading `:`. This is optional,
+    /// and will be [`None`] if not provided.
+    pub source: Option<&'a str>,
+
+    /// This line's command.
+    pub command: &'a str,
+
+    /// This line's params.
+    pub params: Vec<&'a str>,
+}
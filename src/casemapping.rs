@@ -0,0 +1,138 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// The casemapping used to compare nicks and channel names, as given by
+/// the `CASEMAPPING` ISUPPORT token.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaseMapping {
+    /// Only the letters `A-Z` are mapped to `a-z`.
+    Ascii,
+
+    /// As with [`CaseMapping::Ascii`], but `[]\~` are also mapped to
+    /// `{}|^`. This is the default if a server doesn't advertise a
+    /// casemapping.
+    #[default]
+    Rfc1459,
+
+    /// As with [`CaseMapping::Rfc1459`], but `~` and `^` are not mapped.
+    StrictRfc1459,
+}
+
+impl CaseMapping {
+    /// Gets a casemapping from its `CASEMAPPING` ISUPPORT token value.
+    ///
+    /// # Arguments
+    /// - `value` - The token's value.
+    ///
+    /// # Returns
+    /// - [`Option<CaseMapping>`] - The casemapping, or [`None`] if it is
+    ///   not known.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::CaseMapping;
+    ///
+    /// assert_eq!(CaseMapping::from_isupport("ascii"), Some(CaseMapping::Ascii));
+    /// ```
+    ///
+    pub fn from_isupport(value: &str) -> Option<Self> {
+        match value {
+            "ascii" => Some(Self::Ascii),
+            "rfc1459" => Some(Self::Rfc1459),
+            "strict-rfc1459" => Some(Self::StrictRfc1459),
+            _ => None,
+        }
+    }
+
+    /// Lowercases a nick or channel name using this casemapping.
+    ///
+    /// # Arguments
+    /// - `text` - The text to lowercase.
+    ///
+    /// # Returns
+    /// - [`String`] - The lowercased text.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::CaseMapping;
+    ///
+    /// assert_eq!(CaseMapping::Rfc1459.to_lower("#Rick[]"), "#rick{}");
+    /// assert_eq!(CaseMapping::Ascii.to_lower("#Rick[]"), "#rick[]");
+    /// ```
+    ///
+    pub fn to_lower(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| match (self, c) {
+                (Self::Rfc1459 | Self::StrictRfc1459, '[') => '{',
+                (Self::Rfc1459 | Self::StrictRfc1459, ']') => '}',
+                (Self::Rfc1459 | Self::StrictRfc1459, '\\') => '|',
+                (Self::Rfc1459, '~') => '^',
+                (_, c) => c.to_ascii_lowercase(),
+            })
+            .collect()
+    }
+
+    /// Checks whether two nicks or channel names are equal under this
+    /// casemapping.
+    ///
+    /// # Arguments
+    /// - `a` - The first name.
+    /// - `b` - The second name.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether the names are equal.
+    ///
+    pub fn eq(&self, a: &str, b: &str) -> bool {
+        a.len() == b.len() && self.to_lower(a) == self.to_lower(b)
+    }
+}
+
+#[cfg(test)]
+mod test_casemapping {
+    use super::CaseMapping;
+
+    #[test]
+    fn test_rfc1459() {
+        assert!(CaseMapping::Rfc1459.eq("#Foo[]", "#foo{}"));
+        assert!(CaseMapping::Rfc1459.eq("Rick\\~", "rick|^"));
+    }
+
+    #[test]
+    fn test_strict_rfc1459() {
+        assert!(CaseMapping::StrictRfc1459.eq("#Foo[]", "#foo{}"));
+        assert!(!CaseMapping::StrictRfc1459.eq("rick~", "rick^"));
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert!(CaseMapping::Ascii.eq("#Foo", "#foo"));
+        assert!(!CaseMapping::Ascii.eq("#Foo[]", "#foo{}"));
+    }
+}
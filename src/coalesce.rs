@@ -0,0 +1,138 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::Line;
+
+/// Merges runs of consecutive `PRIVMSG` fragments into single lines.
+/// Some setups send long messages as several back-to-back `PRIVMSG`s;
+/// this joins each run back into one message. Fragments are only
+/// merged if they share a source, and optionally a target. Merged lines
+/// keep the tags and target of their first fragment.
+///
+/// # Arguments
+/// - `lines` - The lines to merge, in the order they were received.
+/// - `same_target_only` - Whether fragments must also share a target to
+///   be merged.
+/// - `separator` - The text to join fragments with.
+///
+/// # Returns
+/// - [`Vec<Line>`] - The lines, with consecutive fragments merged.
+///   Lines other than `PRIVMSG`s are left as they are.
+///
+/// # Example
+/// ```
+/// let lines = ircparser::parse(
+///     ":rick!user@host PRIVMSG #rickastley :Never gonna give\r\n:rick!user@host PRIVMSG #rickastley :you up!\r\n",
+/// ).unwrap();
+///
+/// let merged = ircparser::coalesce_consecutive(lines, true, " ");
+///
+/// assert_eq!(merged.len(), 1);
+/// assert_eq!(merged[0].params[1], "Never gonna give you up!");
+/// ```
+///
+pub fn coalesce_consecutive(
+    lines: impl IntoIterator<Item = Line>,
+    same_target_only: bool,
+    separator: &str,
+) -> Vec<Line> {
+    let is_fragment = |x: &Line| x.command.eq_ignore_ascii_case("PRIVMSG") && x.params.len() == 2;
+    let mut merged: Vec<Line> = Vec::new();
+
+    for line in lines {
+        if let Some(last) = merged.last_mut() {
+            if is_fragment(last)
+                && is_fragment(&line)
+                && last.source.is_some()
+                && last.source == line.source
+                && (!same_target_only || last.params[0] == line.params[0])
+            {
+                last.params[1].push_str(separator);
+                last.params[1].push_str(&line.params[1]);
+                continue;
+            }
+        }
+
+        merged.push(line);
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod test_coalesce {
+    use super::coalesce_consecutive;
+    use crate::Line;
+
+    fn privmsg(source: &str, target: &str, text: &str) -> Line {
+        let mut line = Line::from(("PRIVMSG", vec![target, text]));
+        line.source = Some(source.to_string());
+        line
+    }
+
+    #[test]
+    fn test_merge_fragments() {
+        let lines = vec![
+            privmsg("rick!user@host", "#rickastley", "Never gonna give"),
+            privmsg("rick!user@host", "#rickastley", "you up!"),
+            privmsg("astley!user@host", "#rickastley", "Never gonna let"),
+        ];
+        let merged = coalesce_consecutive(lines, true, " ");
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].params[1], "Never gonna give you up!");
+        assert_eq!(merged[1].params[1], "Never gonna let");
+    }
+
+    #[test]
+    fn test_merge_different_targets() {
+        let lines = vec![
+            privmsg("rick!user@host", "#rick", "Never gonna give"),
+            privmsg("rick!user@host", "#astley", "you up!"),
+        ];
+
+        assert_eq!(coalesce_consecutive(lines.clone(), true, " ").len(), 2);
+
+        let merged = coalesce_consecutive(lines, false, "\n");
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].params, vec!["#rick", "Never gonna give\nyou up!"]);
+    }
+
+    #[test]
+    fn test_merge_interrupted() {
+        let lines = vec![
+            privmsg("rick!user@host", "#rickastley", "Never gonna give"),
+            Line::from(("PING", vec!["irc.example.com"])),
+            privmsg("rick!user@host", "#rickastley", "you up!"),
+        ];
+
+        assert_eq!(coalesce_consecutive(lines, true, " ").len(), 3);
+    }
+}
@@ -0,0 +1,109 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// Options controlling how messages are parsed.
+///
+/// The default configuration matches the behaviour of
+/// [ircparser::parse](super::parse).
+///
+/// # Example
+/// ```
+/// let config = ircparser::ParseConfig {
+///     tolerant: true,
+///     ..Default::default()
+/// };
+///
+/// assert!(config.tolerant);
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct ParseConfig {
+    /// Whether to accept a tag block that appears after the source
+    /// (`:source @tags COMMAND`) rather than before it. This ordering
+    /// is invalid, but is sent by some noncompliant servers, so
+    /// [parse_with_warnings](super::parse_with_warnings) raises a
+    /// warning when it is accepted. Tag sections
+    /// longer than the IRCv3 limit of 8191 bytes are also accepted, as
+    /// are trailing params with no space before them (`PRIVMSG:hi`).
+    pub tolerant: bool,
+
+    /// The maximum number of params a line may have. Once this many
+    /// params have been parsed, the remainder of the line is coalesced
+    /// into the last param, as RFC1459 does with its limit of 15. This
+    /// is optional, and there will be no limit if [`None`].
+    pub max_params: Option<usize>,
+
+    /// Whether to convert commands to uppercase, as IRC commands are
+    /// case-insensitive. Numerics are unaffected.
+    pub normalize_command: bool,
+
+    /// The maximum length of a tag's value in bytes, after unescaping
+    /// (even if [`raw_tag_values`](ParseConfig::raw_tag_values) is set).
+    /// Lines with a longer tag value will fail to parse. This is
+    /// optional, and there will be no limit if [`None`].
+    pub max_tag_value_len: Option<usize>,
+
+    /// Whether to reject tag values containing unknown escape sequences
+    /// (such as `\x`), or a trailing lone backslash. By default, the
+    /// backslash is dropped from these, as the IRCv3 specification
+    /// requires.
+    pub strict_tag_escapes: bool,
+
+    /// Whether to keep tag values exactly as they were sent, without
+    /// unescaping them. Combined with [`Line::to_string_raw_tags`], this
+    /// allows lines to be forwarded without altering their tags, even
+    /// if they contain unknown escape sequences.
+    ///
+    /// [`Line::to_string_raw_tags`]: crate::Line::to_string_raw_tags
+    pub raw_tag_values: bool,
+
+    /// Whether to convert the vendor part of namespaced tag keys to
+    /// lowercase, as vendors are case-insensitive domain names. The rest
+    /// of the key is left as it is, so `Example.COM/Foo` becomes
+    /// `example.com/Foo`.
+    pub normalize_tag_vendors: bool,
+
+    /// Whether to reject tag keys that are not valid as per the IRCv3
+    /// message tags specification. Valid keys are made up of an
+    /// optional `+` client prefix, an optional vendor followed by `/`,
+    /// and a name containing only letters, digits, and `-`.
+    pub strict_tag_keys: bool,
+
+    /// Whether to remove the leading `:` from [`Line::source`]. By
+    /// default it is kept. Lines are serialized with the `:` either way.
+    ///
+    /// [`Line::source`]: crate::Line::source
+    pub strip_source_colon: bool,
+
+    /// The maximum number of lines a single message may contain. Parsing
+    /// stops with an error at the first line over the limit. This is
+    /// optional, and there will be no limit if [`None`].
+    pub max_lines: Option<usize>,
+}
@@ -0,0 +1,233 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A run of text sharing the same formatting, as returned by
+/// [ircparser::parse_formatting](super::parse_formatting).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormattedSpan {
+    /// This span's text, with all formatting codes removed.
+    pub text: String,
+
+    /// This span's foreground colour code. This is optional, and will
+    /// be [`None`] if the default colour should be used.
+    pub fg: Option<u8>,
+
+    /// This span's background colour code. This is optional, and will
+    /// be [`None`] if the default colour should be used.
+    pub bg: Option<u8>,
+
+    /// Whether this span is bold.
+    pub bold: bool,
+
+    /// Whether this span is italic.
+    pub italic: bool,
+
+    /// Whether this span is underlined.
+    pub underline: bool,
+}
+
+/// Reads a colour code of up to two digits.
+fn read_colour(chars: &mut Peekable<Chars>) -> Option<u8> {
+    let mut colour: Option<u8> = None;
+
+    for _ in 0..2 {
+        match chars.peek().and_then(|c| c.to_digit(10)) {
+            Some(x) => {
+                colour = Some(colour.unwrap_or(0) * 10 + x as u8);
+                chars.next();
+            }
+            None => break,
+        }
+    }
+
+    colour
+}
+
+/// Splits text into runs of identically formatted text, decoding mIRC
+/// style formatting codes.
+///
+/// Bold (`\x02`), italic (`\x1d`), underline (`\x1f`), colour (`\x03`),
+/// and reset (`\x0f`) codes are decoded. Colour codes take the form
+/// `\x03fg` or `\x03fg,bg`, where each colour is one or two digits, and
+/// a `\x03` with no colour resets both colours. Other formatting codes
+/// (`\x11`, `\x16`, and `\x1e`) are removed from the text but otherwise
+/// ignored.
+///
+/// # Arguments
+/// - `text` - The text to decode.
+///
+/// # Returns
+/// - [`Vec<FormattedSpan>`] - The formatted runs of text, in order.
+///   Runs without any text are left out.
+///
+/// # Example
+/// ```
+/// let spans = ircparser::parse_formatting("Never gonna \x02\x0304give\x0f you up!");
+///
+/// assert_eq!(spans[0].text, "Never gonna ");
+/// assert_eq!(spans[1].text, "give");
+/// assert!(spans[1].bold);
+/// assert_eq!(spans[1].fg, Some(4));
+/// assert_eq!(spans[2].text, " you up!");
+/// assert!(!spans[2].bold);
+/// ```
+///
+pub fn parse_formatting(text: &str) -> Vec<FormattedSpan> {
+    let mut spans: Vec<FormattedSpan> = Vec::new();
+    let mut current = FormattedSpan::default();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if !matches!(
+            c,
+            '\x02' | '\x03' | '\x0f' | '\x11' | '\x16' | '\x1d' | '\x1e' | '\x1f'
+        ) {
+            current.text.push(c);
+            continue;
+        }
+
+        if !current.text.is_empty() {
+            let next = FormattedSpan {
+                text: String::new(),
+                ..current.clone()
+            };
+            spans.push(std::mem::replace(&mut current, next));
+        }
+
+        match c {
+            '\x02' => current.bold = !current.bold,
+            '\x1d' => current.italic = !current.italic,
+            '\x1f' => current.underline = !current.underline,
+            '\x0f' => current = FormattedSpan::default(),
+            '\x03' => {
+                current.fg = read_colour(&mut chars);
+                if current.fg.is_none() {
+                    current.bg = None;
+                    continue;
+                }
+
+                let mut lookahead = chars.clone();
+                if lookahead.next() == Some(',')
+                    && lookahead.peek().is_some_and(|c| c.is_ascii_digit())
+                {
+                    chars.next();
+                    current.bg = read_colour(&mut chars);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !current.text.is_empty() {
+        spans.push(current);
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod test_formatting {
+    use super::{parse_formatting, FormattedSpan};
+
+    #[test]
+    fn test_colour_and_bold() {
+        let spans = parse_formatting("Never \x02gonna \x034,12give\x02 you\x03 up\x0f!");
+
+        assert_eq!(
+            spans,
+            vec![
+                FormattedSpan {
+                    text: String::from("Never "),
+                    ..Default::default()
+                },
+                FormattedSpan {
+                    text: String::from("gonna "),
+                    bold: true,
+                    ..Default::default()
+                },
+                FormattedSpan {
+                    text: String::from("give"),
+                    fg: Some(4),
+                    bg: Some(12),
+                    bold: true,
+                    ..Default::default()
+                },
+                FormattedSpan {
+                    text: String::from(" you"),
+                    fg: Some(4),
+                    bg: Some(12),
+                    ..Default::default()
+                },
+                FormattedSpan {
+                    text: String::from(" up"),
+                    ..Default::default()
+                },
+                FormattedSpan {
+                    text: String::from("!"),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_colour_grammar() {
+        let spans = parse_formatting("\x0305,text\x03123\x031,");
+
+        assert_eq!(spans[0].text, ",text");
+        assert_eq!(spans[0].fg, Some(5));
+        assert_eq!(spans[0].bg, None);
+        assert_eq!(spans[1].text, "3");
+        assert_eq!(spans[1].fg, Some(12));
+        assert_eq!(spans[2].text, ",");
+        assert_eq!(spans[2].fg, Some(1));
+    }
+
+    #[test]
+    fn test_italic_underline() {
+        let spans = parse_formatting("\x1d\x1fhi\x1d there");
+
+        assert_eq!(spans.len(), 2);
+        assert!(spans[0].italic && spans[0].underline);
+        assert!(!spans[1].italic && spans[1].underline);
+    }
+
+    #[test]
+    fn test_plain() {
+        let spans = parse_formatting("Never gonna give you up!");
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].text, "Never gonna give you up!");
+        assert!(parse_formatting("").is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::Line;
+use std::collections::HashMap;
+
+/// The tokens a server has advertised in its `RPL_ISUPPORT` (`005`)
+/// replies. Servers send these across several lines, and may later
+/// change or remove (with `-KEY`) previously advertised tokens.
+///
+/// # Example
+/// ```
+/// let msg = ":irc.example.com 005 rick CHANTYPES=#& NICKLEN=30 EXCEPTS :are supported by this server\r\n\
+///            :irc.example.com 005 rick -EXCEPTS NICKLEN=31 :are supported by this server\r\n";
+///
+/// let mut isupport = ircparser::ISupport::new();
+/// for line in ircparser::parse(msg).unwrap() {
+///     isupport.push(&line);
+/// }
+///
+/// assert_eq!(isupport.get("CHANTYPES"), Some("#&"));
+/// assert_eq!(isupport.get("NICKLEN"), Some("31"));
+/// assert!(!isupport.contains("EXCEPTS"));
+/// ```
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ISupport {
+    /// The advertised tokens. Tokens without a value have an empty one.
+    /// Values are stored exactly as sent.
+    pub tokens: HashMap<String, String>,
+}
+
+impl ISupport {
+    /// Creates a new, empty [`ISupport`].
+    ///
+    /// # Returns
+    /// - [`ISupport`] - The new [`ISupport`] instance.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a line to this accumulator. Lines other than `005` replies
+    /// are ignored. Tokens of the form `-KEY` remove `KEY`.
+    ///
+    /// # Arguments
+    /// - `line` - The line to feed.
+    ///
+    pub fn push(&mut self, line: &Line) {
+        // The first param is the client, and the last is the
+        // human-readable "are supported by this server" text.
+        if line.command != "005" || line.params.len() < 3 {
+            return;
+        }
+
+        for token in &line.params[1..line.params.len() - 1] {
+            if let Some(key) = token.strip_prefix('-') {
+                self.tokens.remove(key);
+                continue;
+            }
+
+            let (key, value) = token.split_once('=').unwrap_or((token, ""));
+            if !key.is_empty() {
+                self.tokens.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+
+    /// Gets the value of an advertised token.
+    ///
+    /// # Arguments
+    /// - `key` - The token's key.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The token's value (which will be empty if it
+    ///   has none), or [`None`] if it has not been advertised.
+    ///
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.tokens.get(key).map(|x| x.as_str())
+    }
+
+    /// Checks whether a token has been advertised.
+    ///
+    /// # Arguments
+    /// - `key` - The token's key.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether the token has been advertised.
+    ///
+    pub fn contains(&self, key: &str) -> bool {
+        self.tokens.contains_key(key)
+    }
+}
+
+#[cfg(test)]
+mod test_isupport {
+    use super::ISupport;
+    use crate::Line;
+
+    fn isupport(tokens: &[&str]) -> Line {
+        let mut params = vec!["rick"];
+        params.extend(tokens);
+        params.push("are supported by this server");
+        Line::from(("005", params))
+    }
+
+    #[test]
+    fn test_isupport() {
+        let mut state = ISupport::new();
+        state.push(&isupport(&["CHANTYPES=#&", "EXCEPTS", "PREFIX=(ov)@+"]));
+
+        assert_eq!(state.get("CHANTYPES"), Some("#&"));
+        assert_eq!(state.get("EXCEPTS"), Some(""));
+        assert_eq!(state.get("PREFIX"), Some("(ov)@+"));
+        assert_eq!(state.tokens.len(), 3);
+    }
+
+    #[test]
+    fn test_isupport_negation() {
+        let mut state = ISupport::new();
+        state.push(&isupport(&["CHANTYPES=#&", "EXCEPTS", "NICKLEN=30"]));
+        state.push(&isupport(&["-EXCEPTS", "NICKLEN=31", "-UNKNOWN"]));
+
+        assert!(!state.contains("EXCEPTS"));
+        assert_eq!(state.get("NICKLEN"), Some("31"));
+        assert_eq!(state.get("CHANTYPES"), Some("#&"));
+    }
+
+    #[test]
+    fn test_isupport_other() {
+        let mut state = ISupport::new();
+        state.push(&Line::from(("004", vec!["rick", "irc.example.com", "x"])));
+
+        assert!(state.tokens.is_empty());
+    }
+}
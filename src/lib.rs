@@ -55,22 +55,139 @@
 //! };
 //! ```
 
+mod batch;
+#[cfg(feature = "bench")]
+mod bench;
+mod borrowed;
+mod casemapping;
+mod coalesce;
+mod config;
+mod formatting;
+mod isupport;
 mod line;
+mod motd;
+mod names;
+mod parser;
+mod quote;
+mod reply;
+mod source;
+mod spans;
+#[cfg(feature = "tokio")]
+mod stream;
+mod tags;
+mod time;
+mod twitch;
+mod typing;
+mod validate;
 
+pub use batch::Batch;
+#[cfg(feature = "bench")]
+pub use bench::bench_parse;
+pub use borrowed::BorrowedLine;
+pub use casemapping::CaseMapping;
+pub use coalesce::coalesce_consecutive;
+pub use config::ParseConfig;
+pub use formatting::{parse_formatting, FormattedSpan};
+pub use isupport::ISupport;
 pub use line::Line;
-use std::collections::{HashMap, VecDeque};
+pub use motd::MotdBuilder;
+pub use names::{ChannelVisibility, Names, NamesBuilder, NamesEntry};
+pub use parser::Parser;
+pub use quote::{low_level_dequote, low_level_quote};
+pub use reply::{Severity, StandardReply};
+pub use source::Source;
+pub use spans::LineSpans;
+use std::collections::{HashMap, HashSet, VecDeque};
+#[cfg(feature = "tokio")]
+pub use stream::AsyncStreamParser;
+pub use tags::{TagDecode, Tags};
+pub use twitch::parse_twitch_emotes;
+pub use typing::TypingState;
+pub use validate::{is_valid_channel, is_valid_nick, validate, IssueSeverity, ValidationIssue};
 
 type ParseResult<T> = Result<T, ParseError>;
+type Warnings = Vec<(usize, ParseError)>;
+
+/// The maximum length of a line's tag section in bytes, including the
+/// leading `@` and trailing space.
+const MAX_TAGS_LEN: usize = 8191;
+
+/// The kind of a [`ParseError`], for callers which need to tell errors
+/// apart without inspecting their details.
+///
+/// # Example
+/// ```
+/// let tags = "a".repeat(8191);
+/// let e = ircparser::parse(&format!("@{tags} PING :x\r\n")).unwrap_err();
+///
+/// assert_eq!(e.kind(), &ircparser::ParseErrorKind::TagsTooLong { len: 8193 });
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The tag section is longer than the 8191 bytes IRCv3 allows.
+    TagsTooLong {
+        /// The length of the tag section in bytes, including the
+        /// leading `@` and trailing space.
+        len: usize,
+    },
+    /// A line read by a stream parser is longer than its maximum line
+    /// length.
+    TooLong {
+        /// The maximum line length in bytes.
+        max: usize,
+    },
+    /// A tag's value is longer than
+    /// [`ParseConfig::max_tag_value_len`] allows.
+    TagValueTooLong {
+        /// The key of the tag.
+        key: String,
+    },
+    /// A tag's value contains an escape sequence that IRCv3 doesn't
+    /// define, and [`ParseConfig::strict_tag_escapes`] is set.
+    BadTagEscape {
+        /// The unknown escape sequence, including its backslash.
+        seq: String,
+    },
+    /// A tag's key contains characters outside `A-Za-z0-9-./+`, and
+    /// [`ParseConfig::strict_tag_keys`] is set.
+    BadTagKey {
+        /// The invalid key.
+        key: String,
+    },
+    /// A message has more lines than [`ParseConfig::max_lines`] allows.
+    TooManyLines {
+        /// The maximum number of lines.
+        max: usize,
+    },
+    /// A stream parser's reader failed. The stream ends after yielding
+    /// this error.
+    Io {
+        /// The kind of the underlying I/O error.
+        kind: std::io::ErrorKind,
+    },
+    /// A line read by a stream parser is not valid UTF-8.
+    InvalidUtf8,
+    /// A tag block appears after the source rather than before it. This
+    /// is only raised as a warning by [`parse_with_warnings`], as the
+    /// line is otherwise parsed as normal.
+    TagsAfterSource,
+    /// Any other error. The error's details describe what went wrong.
+    Other,
+}
 
 /// Exception thrown when an error occurs during message parsing.
 #[derive(Debug, Clone)]
 pub struct ParseError {
     /// The details of this error.
     pub details: String,
+    kind: ParseErrorKind,
 }
 
 impl ParseError {
-    /// Generates a new [`ParseError`].
+    /// Generates a new [`ParseError`] of kind
+    /// [`Other`](ParseErrorKind::Other).
     ///
     /// # Arguments
     /// - `details` - The details of this error.
@@ -83,10 +200,39 @@ impl ParseError {
     /// ```
     ///
     pub fn new(details: &str) -> Self {
+        Self::with_kind(ParseErrorKind::Other, details)
+    }
+
+    /// Generates a new [`ParseError`] of the given kind.
+    ///
+    /// # Arguments
+    /// - `kind` - The kind of this error.
+    /// - `details` - The details of this error.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::{ParseError, ParseErrorKind};
+    ///
+    /// let e = ParseError::with_kind(ParseErrorKind::TagsTooLong { len: 8192 }, "err");
+    ///
+    /// assert_eq!(e.kind(), &ParseErrorKind::TagsTooLong { len: 8192 });
+    /// ```
+    ///
+    pub fn with_kind(kind: ParseErrorKind, details: &str) -> Self {
         Self {
             details: details.into(),
+            kind,
         }
     }
+
+    /// Gets the kind of this error.
+    ///
+    /// # Returns
+    /// - [`&ParseErrorKind`](ParseErrorKind) - The kind of this error.
+    ///
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
 }
 
 impl std::fmt::Display for ParseError {
@@ -95,26 +241,226 @@ impl std::fmt::Display for ParseError {
     }
 }
 
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for std::io::Error {
+    /// Converts a [`ParseError`] into an [`std::io::Error`] of kind
+    /// [`InvalidData`](std::io::ErrorKind::InvalidData).
+    fn from(e: ParseError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
 fn find_index(text: &str, char: char, start: usize) -> Option<usize> {
-    for (k, _) in text.match_indices(char) {
-        if k > start {
-            return Some(k);
+    text.match_indices(char)
+        .map(|(k, _)| k)
+        .find(|&k| k > start)
+}
+
+fn parse_tags(
+    text: &str,
+    config: &ParseConfig,
+) -> ParseResult<(HashMap<String, String>, HashSet<String>)> {
+    let mut tags: HashMap<String, String> = HashMap::new();
+    let mut bare_tags: HashSet<String> = HashSet::new();
+
+    for part in text.split(';').filter(|x| !x.is_empty()) {
+        // Tags without a value are treated as having an empty one, but
+        // are remembered so they can be written back out the same way.
+        let (key, value, bare) = match part.find('=') {
+            Some(idx) => (&part[..idx], &part[idx + 1..], false),
+            None => (part, "", true),
+        };
+
+        if config.strict_tag_keys && !tags::is_valid_key(key) {
+            return Err(ParseError::with_kind(
+                ParseErrorKind::BadTagKey { key: key.into() },
+                &format!("invalid tag key {key:?}"),
+            ));
+        }
+
+        if config.strict_tag_escapes {
+            if let Some((_, seq)) = tags::unknown_escape(value) {
+                return Err(ParseError::with_kind(
+                    ParseErrorKind::BadTagEscape { seq: seq.into() },
+                    &format!("value of tag {key:?} contains unknown escape sequence {seq:?}"),
+                ));
+            }
+        }
+
+        let unescaped = tags::unescape_value(value);
+
+        // Limits apply to the unescaped value, even in raw mode.
+        if let Some(max) = config.max_tag_value_len {
+            if unescaped.len() > max {
+                return Err(ParseError::with_kind(
+                    ParseErrorKind::TagValueTooLong { key: key.into() },
+                    &format!(
+                        "value of tag {key:?} is too long ({} bytes, maximum is {max})",
+                        unescaped.len()
+                    ),
+                ));
+            }
+        }
+
+        let value = if config.raw_tag_values {
+            value.to_string()
+        } else {
+            unescaped
+        };
+
+        let key = match key.find('/') {
+            Some(idx) if config.normalize_tag_vendors => {
+                format!("{}{}", key[..idx].to_ascii_lowercase(), &key[idx..])
+            }
+            _ => key.to_string(),
+        };
+
+        if bare {
+            bare_tags.insert(key.clone());
+        } else {
+            bare_tags.remove(&key);
+        }
+        tags.insert(key, value);
+    }
+
+    Ok((tags, bare_tags))
+}
+
+fn no_command() -> ParseError {
+    ParseError::new("line has no command")
+}
+
+fn line_spans(line: &str, config: &ParseConfig) -> ParseResult<LineSpans> {
+    let mut idx = 0;
+    let mut spans = LineSpans::default();
+
+    // Parse tags component.
+    if line.starts_with('@') {
+        idx = line.find(' ').ok_or_else(no_command)?;
+        spans.tags = Some(1..idx);
+        idx += 1;
+
+        // The tag section includes the leading @ and trailing space.
+        if !config.tolerant && idx > MAX_TAGS_LEN {
+            return Err(ParseError::with_kind(
+                ParseErrorKind::TagsTooLong { len: idx },
+                &format!("tag section is too long ({idx} bytes, maximum is {MAX_TAGS_LEN})"),
+            ));
+        }
+    }
+
+    // Parse source component.
+    if line[idx..].starts_with(':') {
+        let end_idx = find_index(line, ' ', idx).ok_or_else(no_command)?;
+        spans.source = Some(idx..end_idx);
+        idx = end_idx + 1;
+
+        // Some noncompliant servers send tags after the source.
+        if config.tolerant && spans.tags.is_none() && line[idx..].starts_with('@') {
+            let end_idx = find_index(line, ' ', idx).ok_or_else(no_command)?;
+            spans.tags = Some(idx + 1..end_idx);
+            idx = end_idx + 1;
+        }
+    }
+
+    // Parse command component. Commands without params (such as a bare
+    // QUIT) run to the end of the line.
+    let end_idx = line[idx..].find(' ').map_or(line.len(), |x| idx + x);
+    if end_idx == idx {
+        return Err(no_command());
+    }
+
+    match line[idx..end_idx].find(':').map(|x| idx + x) {
+        Some(colon) if colon == idx => return Err(no_command()),
+        // Some noncompliant clients leave out the space before the
+        // trailing param (`PRIVMSG:hi`).
+        Some(colon) if config.tolerant => {
+            spans.command = idx..colon;
+            idx = colon;
+        }
+        Some(_) => {
+            return Err(ParseError::new(&format!(
+                "command {:?} contains a colon (is the space before the trailing param missing?)",
+                &line[idx..end_idx]
+            )));
+        }
+        None => {
+            spans.command = idx..end_idx;
+            idx = end_idx + 1;
+        }
+    }
+
+    // Parse params component.
+    while idx < line.len() {
+        if line[idx..].starts_with(':') {
+            spans.params.push(idx + 1..line.len());
+            break;
+        }
+
+        // With a param limit, the last param takes the rest of the line.
+        if let Some(max) = config.max_params {
+            if spans.params.len() + 1 >= max {
+                spans.params.push(idx..line.len());
+                break;
+            }
+        }
+
+        let end_idx = line[idx..].find(' ').map_or(line.len(), |x| idx + x);
+        spans.params.push(idx..end_idx);
+        idx = end_idx + 1;
+    }
+
+    Ok(spans)
+}
+
+fn parse_line(line: &str, config: &ParseConfig) -> ParseResult<Line> {
+    line_from_spans(line, line_spans(line, config)?, config)
+}
+
+fn line_from_spans(line: &str, spans: LineSpans, config: &ParseConfig) -> ParseResult<Line> {
+    let (tags, bare_tags) = match spans.tags {
+        Some(r) => parse_tags(&line[r], config)?,
+        None => Default::default(),
+    };
+    let source = spans.source.map(|r| {
+        if config.strip_source_colon {
+            line[r.start + 1..r.end].to_string()
+        } else {
+            line[r].to_string()
         }
+    });
+    // Only a trailing param can follow a colon.
+    let has_trailing = spans
+        .params
+        .last()
+        .is_some_and(|r| line[..r.start].ends_with(':'));
+    let params = spans
+        .params
+        .into_iter()
+        .map(|r| line[r].to_string())
+        .collect();
+
+    let mut line = Line::new(tags, source, &line[spans.command], params);
+    line.bare_tags = bare_tags;
+    line.has_trailing = has_trailing;
+    if config.normalize_command {
+        line.command.make_ascii_uppercase();
     }
 
-    None
+    Ok(line)
 }
 
 /// Parses an IRC message.
 ///
 /// # Arguments
 /// - `text` - The text you want to parse. This can comprise of multiple
-/// lines. In this case, each line (separated by a newline character)
-/// will be a separate element in the return value.
+///   lines. In this case, each line (separated by a newline character)
+///   will be a separate element in the return value.
 ///
 /// # Returns
 /// - [`VecDeque<Line>`] - A [`VecDeque`] of all parsed [`Line`]s. This
-/// will be empty if no valid lines were passed and no errors occur.
+///   will be empty if no valid lines were passed and no errors occur.
 ///
 /// # Example
 /// ```
@@ -145,9 +491,52 @@ fn find_index(text: &str, char: char, start: usize) -> Option<usize> {
 /// [`VecDeque`] of [`Line`] objects instead of a single [`Line`].
 ///
 pub fn parse(text: &str) -> ParseResult<VecDeque<Line>> {
+    parse_with(text, &ParseConfig::default())
+}
+
+/// Parses an IRC message using the given configuration.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+/// - `config` - The [`ParseConfig`] to parse with.
+///
+/// # Returns
+/// - [`VecDeque<Line>`] - A [`VecDeque`] of all parsed [`Line`]s.
+///
+/// # Example
+/// ```
+/// let config = ircparser::ParseConfig {
+///     tolerant: true,
+///     ..Default::default()
+/// };
+/// let msg = ":nick!user@host @id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n";
+///
+/// let line = ircparser::parse_with(msg, &config).unwrap().pop_front().unwrap();
+///
+/// assert_eq!(&line.tags["id"], "123");
+/// assert_eq!(line.command, "PRIVMSG");
+/// ```
+///
+pub fn parse_with(text: &str, config: &ParseConfig) -> ParseResult<VecDeque<Line>> {
     let mut parsed_lines: VecDeque<Line> = VecDeque::new();
+    parse_into_with(text, config, &mut parsed_lines)?;
+    Ok(parsed_lines)
+}
 
-    for line in text.split("\r\n") {
+fn parse_into_with(text: &str, config: &ParseConfig, out: &mut VecDeque<Line>) -> ParseResult<()> {
+    parse_lines(text, config, out, &mut Vec::new())
+}
+
+fn parse_lines(
+    text: &str,
+    config: &ParseConfig,
+    out: &mut VecDeque<Line>,
+    warnings: &mut Warnings,
+) -> ParseResult<()> {
+    let mut count = 0;
+
+    for (i, line) in text.split("\r\n").enumerate() {
         if line.is_empty() {
             // If the line length is 0, we can assume the previous line
             // ended in \r\n, and that this line doesn't need to be
@@ -155,129 +544,501 @@ pub fn parse(text: &str) -> ParseResult<VecDeque<Line>> {
             continue;
         }
 
-        let mut idx = 0;
-        let mut tags: HashMap<String, String> = HashMap::new();
-        let mut source: Option<String> = None;
-
-        // Parse tags component.
-        if line.starts_with('@') {
-            idx = line.find(' ').unwrap();
-
-            for part in Some(&line[1..idx]).unwrap().split(';') {
-                let kv: Vec<&str> = part.split('=').collect();
-                tags.insert(kv[0].to_string(), kv[1].to_string());
+        if let Some(max) = config.max_lines {
+            if count >= max {
+                return Err(ParseError::with_kind(
+                    ParseErrorKind::TooManyLines { max },
+                    &format!("message has too many lines (maximum is {max})"),
+                ));
             }
-
-            idx += 1;
         }
 
-        // Parse source component.
-        if line.chars().nth(idx).unwrap() == ':' {
-            let end_idx = find_index(line, ' ', idx).unwrap();
-            source = Some(line[idx..end_idx].to_string());
-            idx = end_idx + 1;
+        let spans = line_spans(line, config)?;
+
+        // Tags can only follow the source in tolerant mode.
+        if let (Some(tags), Some(source)) = (&spans.tags, &spans.source) {
+            if tags.start > source.start {
+                warnings.push((
+                    i,
+                    ParseError::with_kind(
+                        ParseErrorKind::TagsAfterSource,
+                        "tags appear after the source",
+                    ),
+                ));
+            }
         }
 
-        // Parse command component.
-        let end_idx = find_index(line, ' ', idx).unwrap();
-        let command = &line[idx..end_idx];
-        idx = end_idx + 1;
+        out.push_back(line_from_spans(line, spans, config)?);
+        count += 1;
+    }
 
-        let c_idx = match find_index(line, ':', idx) {
-            Some(x) => x - 1,
-            None => line.len(),
-        };
+    Ok(())
+}
 
-        // Parse params component.
-        let mut params: Vec<String> = line[idx..c_idx].split(' ').map(|x| x.to_string()).collect();
-        if c_idx != line.len() {
-            params.push(line[c_idx + 2..].to_string());
-        }
+/// Parses an IRC message using the given configuration, also returning
+/// any warnings raised along the way. Warnings are raised for problems
+/// that [`ParseConfig::tolerant`] allows the parser to work around,
+/// such as tags which appear after the source.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+/// - `config` - The [`ParseConfig`] to parse with.
+///
+/// # Returns
+/// - [`VecDeque<Line>`] - A [`VecDeque`] of all parsed [`Line`]s.
+/// - [`Vec<(usize, ParseError)>`] - The warnings for each line which
+///   raised one, alongside the line's index in `text` (counting from 0,
+///   and including empty lines), as with [`parse_partial`].
+///
+/// # Example
+/// ```
+/// use ircparser::{ParseConfig, ParseErrorKind};
+///
+/// let config = ParseConfig {
+///     tolerant: true,
+///     ..Default::default()
+/// };
+/// let msg = ":nick!user@host @id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n";
+///
+/// let (lines, warnings) = ircparser::parse_with_warnings(msg, &config).unwrap();
+///
+/// assert_eq!(&lines[0].tags["id"], "123");
+/// assert_eq!(warnings[0].1.kind(), &ParseErrorKind::TagsAfterSource);
+/// ```
+///
+pub fn parse_with_warnings(
+    text: &str,
+    config: &ParseConfig,
+) -> ParseResult<(VecDeque<Line>, Warnings)> {
+    let mut parsed_lines: VecDeque<Line> = VecDeque::new();
+    let mut warnings: Warnings = Vec::new();
+    parse_lines(text, config, &mut parsed_lines, &mut warnings)?;
+    Ok((parsed_lines, warnings))
+}
 
-        parsed_lines.push_back(Line::new(tags, source, command, params));
-    }
+/// Parses an IRC message into a [`SmallVec`](smallvec::SmallVec),
+/// which stores a single line inline rather than allocating. Messages
+/// with more than one line spill onto the heap as usual.
+///
+/// This requires the `smallvec` feature.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+///
+/// # Returns
+/// - [`SmallVec<[Line; 1]>`](smallvec::SmallVec) - All parsed
+///   [`Line`]s.
+///
+/// # Example
+/// ```
+/// let lines = ircparser::parse_small("PRIVMSG #rickastley :Never gonna give you up!\r\n").unwrap();
+///
+/// assert!(!lines.spilled());
+/// assert_eq!(lines[0].command, "PRIVMSG");
+/// ```
+///
+#[cfg(feature = "smallvec")]
+pub fn parse_small(text: &str) -> ParseResult<smallvec::SmallVec<[Line; 1]>> {
+    let config = ParseConfig::default();
 
-    Ok(parsed_lines)
+    text.split("\r\n")
+        .filter(|x| !x.is_empty())
+        .map(|x| parse_line(x, &config))
+        .collect()
 }
 
-#[cfg(test)]
-mod test_lib {
-    use super::parse;
-    use collection_macros::hashmap;
-    use std::collections::HashMap;
+/// Parses an IRC message, appending the parsed lines to an existing
+/// [`VecDeque`]. This allows a single buffer to be reused across calls.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+/// - `out` - The [`VecDeque`] to append parsed [`Line`]s to. If an error
+///   occurs, any lines parsed before it will still have been appended.
+///
+/// # Example
+/// ```
+/// use std::collections::VecDeque;
+///
+/// let mut lines = VecDeque::new();
+/// ircparser::parse_into("PRIVMSG #rickastley :Never gonna give you up!\r\n", &mut lines).unwrap();
+///
+/// assert_eq!(lines.len(), 1);
+/// ```
+///
+pub fn parse_into(text: &str, out: &mut VecDeque<Line>) -> ParseResult<()> {
+    parse_into_with(text, &ParseConfig::default(), out)
+}
 
-    #[test]
-    fn test_single_partial() {
-        let msg = "PRIVMSG #rickastley :Never gonna give you up!\r\n";
+/// Parses an IRC message, collecting errors instead of stopping at the
+/// first one.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+///
+/// # Returns
+/// - [`VecDeque<Line>`] - A [`VecDeque`] of all successfully parsed
+///   [`Line`]s.
+/// - [`Vec<(usize, ParseError)>`] - The errors for each line which
+///   failed to parse, alongside the line's index in `text` (counting
+///   from 0, and including empty lines).
+///
+/// # Example
+/// ```
+/// let tags = "a".repeat(8192);
+/// let msg = format!("PRIVMSG #rickastley :Never gonna give you up!\r\n@{tags} PRIVMSG #rickastley :Never gonna let you down!\r\n");
+/// let (lines, errors) = ircparser::parse_partial(&msg);
+///
+/// assert_eq!(lines.len(), 1);
+/// assert_eq!(errors[0].0, 1);
+/// ```
+///
+pub fn parse_partial(text: &str) -> (VecDeque<Line>, Vec<(usize, ParseError)>) {
+    let config = ParseConfig::default();
+    let mut parsed_lines: VecDeque<Line> = VecDeque::new();
+    let mut errors: Vec<(usize, ParseError)> = Vec::new();
 
-        match parse(msg) {
-            Ok(mut x) => {
-                assert_eq!(x.len(), 1);
-                let line = x.pop_front().unwrap();
+    for (i, line) in text.split("\r\n").enumerate() {
+        if line.is_empty() {
+            continue;
+        }
 
-                assert_eq!(line.tags, HashMap::new());
-                assert_eq!(line.source, None);
-                assert_eq!(line.command, "PRIVMSG");
-                assert_eq!(line.params, vec!["#rickastley", "Never gonna give you up!"]);
-            }
-            Err(e) => {
-                println!("A parsing error occured: {e}");
-                assert!(false);
-            }
+        match parse_line(line, &config) {
+            Ok(x) => parsed_lines.push_back(x),
+            Err(e) => errors.push((i, e)),
         }
     }
 
-    #[test]
-    fn test_single_full() {
-        let msg = "@id=123;name=rick :nick!user@host.tmi.twitch.tv PRIVMSG #rickastley :Never gonna give you up!\r\n";
-        match parse(msg) {
-            Ok(mut x) => {
-                assert_eq!(x.len(), 1);
-                let line = x.pop_front().unwrap();
+    (parsed_lines, errors)
+}
 
-                assert_eq!(
-                    line.tags,
-                    hashmap! {
-                        String::from("id") => String::from("123"),
-                        String::from("name") => String::from("rick"),
-                    }
-                );
-                assert_eq!(
-                    line.source,
-                    Some(String::from(":nick!user@host.tmi.twitch.tv"))
-                );
-                assert_eq!(line.command, "PRIVMSG");
-                assert_eq!(line.params, vec!["#rickastley", "Never gonna give you up!"]);
-            }
-            Err(e) => {
-                println!("A parsing error occured: {e}");
-                return;
-            }
-        };
-    }
+/// Parses an IRC message, keeping only lines with one of the given
+/// commands. Commands are compared case-insensitively. The tags and
+/// params of other lines are never copied, so this does less work than
+/// filtering the output of [`parse`].
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+/// - `commands` - The commands to keep.
+///
+/// # Returns
+/// - [`VecDeque<Line>`] - A [`VecDeque`] of all matching [`Line`]s.
+///
+/// # Example
+/// ```
+/// let msg = "PING :irc.example.com\r\nPRIVMSG #rickastley :Never gonna give you up!\r\n";
+/// let lines = ircparser::parse_filter(msg, &["privmsg"]).unwrap();
+///
+/// assert_eq!(lines.len(), 1);
+/// assert_eq!(lines[0].command, "PRIVMSG");
+/// ```
+///
+pub fn parse_filter(text: &str, commands: &[&str]) -> ParseResult<VecDeque<Line>> {
+    let config = ParseConfig::default();
+    let mut parsed_lines: VecDeque<Line> = VecDeque::new();
 
-    #[test]
-    fn test_readme_example() {
-        let msg = "@id=123;name=rick :nick!user@host.tmi.twitch.tv PRIVMSG #rickastley :Never gonna give you up!\r\n";
-        match parse(msg) {
-            Ok(mut x) => {
-                println!("{x:?}");
-                assert_eq!(x.len(), 1);
-                let line = x.pop_front().unwrap();
+    for line in text.split("\r\n").filter(|x| !x.is_empty()) {
+        let spans = line_spans(line, &config)?;
+        let command = &line[spans.command.clone()];
 
-                assert_eq!(&line.tags["id"], "123");
-                if line.source.is_some() {
-                    assert_eq!(line.source.unwrap(), ":nick!user@host.tmi.twitch.tv");
-                }
-                assert_eq!(line.command, "PRIVMSG");
-                assert_eq!(line.params[0], "#rickastley");
-                assert_eq!(line.params[1], "Never gonna give you up!");
-            }
-            Err(e) => {
-                println!("A parsing error occured: {e}");
-                assert!(false);
-            }
-        };
+        if commands.iter().any(|x| x.eq_ignore_ascii_case(command)) {
+            parsed_lines.push_back(line_from_spans(line, spans, &config)?);
+        }
+    }
+
+    Ok(parsed_lines)
+}
+
+/// Parses an IRC message lazily, skipping any lines that fail to
+/// parse. This is useful for pipelines that should carry on past
+/// malformed input.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+///
+/// # Returns
+/// - [`impl Iterator<Item = Line>`](Iterator) - An iterator over every
+///   successfully parsed [`Line`]. Each line is parsed when it is
+///   reached.
+///
+/// # Example
+/// ```
+/// let msg = "PING :irc.example.com\r\nPRIVMSG #rickastley :Never gonna give you up!\r\n";
+/// let commands: Vec<String> = ircparser::parse_ok_iter(msg).map(|x| x.command).collect();
+///
+/// assert_eq!(commands, vec!["PING", "PRIVMSG"]);
+/// ```
+///
+pub fn parse_ok_iter(text: &str) -> impl Iterator<Item = Line> + '_ {
+    let config = ParseConfig::default();
+
+    text.split("\r\n")
+        .filter(|x| !x.is_empty())
+        .filter_map(move |x| parse_line(x, &config).ok())
+}
+
+/// Runs the parser over arbitrary bytes, for use as a fuzzing target
+/// (such as with `cargo fuzz`). The bytes are decoded lossily, so
+/// invalid UTF-8 is replaced rather than rejected. This never panics.
+///
+/// # Arguments
+/// - `data` - The bytes to parse.
+///
+/// # Returns
+/// - [`usize`] - The number of lines that were parsed successfully.
+///
+/// # Example
+/// ```
+/// assert_eq!(ircparser::fuzz_parse(b"PING :a\r\n@\r\nPING :\xff\r\n"), 2);
+/// ```
+///
+pub fn fuzz_parse(data: &[u8]) -> usize {
+    parse_ok_iter(&String::from_utf8_lossy(data)).count()
+}
+
+/// Parses an IRC message, pairing each line's result with the exact
+/// text it was parsed from.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+///
+/// # Returns
+/// - [`Vec<(String, ParseResult<Line>)>`] - Each line's raw text
+///   (without its `\r\n` terminator) alongside the result of parsing
+///   it. Empty lines are left out.
+///
+/// # Example
+/// ```
+/// let msg = "PING :irc.example.com\r\nPRIVMSG #rickastley :Never gonna give you up!\r\n";
+/// let lines = ircparser::parse_with_raw(msg);
+///
+/// assert_eq!(lines[0].0, "PING :irc.example.com");
+/// assert_eq!(lines[1].1.as_ref().unwrap().command, "PRIVMSG");
+/// ```
+///
+pub fn parse_with_raw(text: &str) -> Vec<(String, ParseResult<Line>)> {
+    let config = ParseConfig::default();
+
+    text.split("\r\n")
+        .filter(|x| !x.is_empty())
+        .map(|x| (x.to_string(), parse_line(x, &config)))
+        .collect()
+}
+
+/// Parses an IRC message into [`BorrowedLine`]s, which borrow their
+/// components from `text` rather than copying them. Tags are stored in
+/// a [`Vec`] rather than a [`HashMap`], so no hashing takes place, and
+/// no text is copied.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+/// - `out` - The [`Vec`] to append parsed lines to. If an error occurs,
+///   any lines parsed before it will still have been appended.
+///
+/// # Example
+/// ```
+/// let mut lines = Vec::new();
+/// ircparser::parse_borrowed_into("@id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n", &mut lines).unwrap();
+///
+/// assert_eq!(lines[0].tags, vec![("id", "123")]);
+/// assert_eq!(lines[0].command, "PRIVMSG");
+/// assert_eq!(lines[0].params, vec!["#rickastley", "Never gonna give you up!"]);
+/// ```
+///
+pub fn parse_borrowed_into<'a>(text: &'a str, out: &mut Vec<BorrowedLine<'a>>) -> ParseResult<()> {
+    let config = ParseConfig::default();
+
+    for line in text.split("\r\n").filter(|x| !x.is_empty()) {
+        let spans = line_spans(line, &config)?;
+
+        let tags = match spans.tags {
+            Some(r) => line[r]
+                .split(';')
+                .filter(|x| !x.is_empty())
+                .map(|x| x.split_once('=').unwrap_or((x, "")))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        out.push(BorrowedLine {
+            tags,
+            source: spans.source.map(|r| &line[r]),
+            command: &line[spans.command],
+            params: spans.params.into_iter().map(|r| &line[r]).collect(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Parses an IRC message, returning the byte ranges of each line's
+/// components instead of their values.
+///
+/// # Arguments
+/// - `text` - The text you want to parse. This can comprise of multiple
+///   lines, as with [`parse`].
+///
+/// # Returns
+/// - [`Vec<LineSpans>`] - The [`LineSpans`] of each line. All ranges are
+///   relative to the start of `text`.
+///
+/// # Example
+/// ```
+/// let msg = ":nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n";
+/// let spans = ircparser::parse_spans(msg).unwrap();
+///
+/// assert_eq!(&msg[spans[0].command.clone()], "PRIVMSG");
+/// assert_eq!(&msg[spans[0].params[1].clone()], "Never gonna give you up!");
+/// ```
+///
+pub fn parse_spans(text: &str) -> ParseResult<Vec<LineSpans>> {
+    let config = ParseConfig::default();
+    let mut spans: Vec<LineSpans> = Vec::new();
+    let mut offset = 0;
+
+    for line in text.split("\r\n") {
+        if !line.is_empty() {
+            spans.push(line_spans(line, &config)?.offset(offset));
+        }
+
+        offset += line.len() + 2;
+    }
+
+    Ok(spans)
+}
+
+/// Checks whether a message survives a round trip through the parser
+/// and formatter unchanged. Each line of `text` is parsed, formatted
+/// with [`Line`]'s [`Display`](std::fmt::Display) implementation, then
+/// parsed again, and the two [`Line`]s compared.
+///
+/// Two lines are compared with [`Line`]'s [`PartialEq`]
+/// implementation, so the form they were written in counts too, such as
+/// whether the last param was written as a trailing param. The
+/// formatter keeps this form, so a line which parses normally will
+/// still compare equal.
+///
+/// # Arguments
+/// - `text` - The text you want to check. This can comprise of multiple
+///   lines, as with [`parse`].
+///
+/// # Returns
+/// - [`bool`] - Whether every line survives the round trip. This will be
+///   `false` if `text` fails to parse.
+///
+/// # Example
+/// ```
+/// let msg = "@id=123 :nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n";
+///
+/// assert!(ircparser::reparse_equals(msg));
+/// ```
+///
+pub fn reparse_equals(text: &str) -> bool {
+    let lines = match parse(text) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    lines.iter().all(|line| match parse(&line.to_string()) {
+        Ok(mut x) => x.len() == 1 && x.pop_front().as_ref() == Some(line),
+        Err(_) => false,
+    })
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::assertions_on_constants,
+    clippy::needless_return,
+    clippy::unnecessary_unwrap
+)]
+mod test_lib {
+    use super::{
+        fuzz_parse, parse, parse_borrowed_into, parse_filter, parse_into, parse_ok_iter,
+        parse_partial, parse_spans, parse_with, parse_with_raw, parse_with_warnings,
+        reparse_equals, Line, ParseConfig, ParseError, ParseErrorKind,
+    };
+    use collection_macros::hashmap;
+    use std::collections::{HashMap, VecDeque};
+
+    #[test]
+    fn test_single_partial() {
+        let msg = "PRIVMSG #rickastley :Never gonna give you up!\r\n";
+
+        match parse(msg) {
+            Ok(mut x) => {
+                assert_eq!(x.len(), 1);
+                let line = x.pop_front().unwrap();
+
+                assert_eq!(line.tags, HashMap::new());
+                assert_eq!(line.source, None);
+                assert_eq!(line.command, "PRIVMSG");
+                assert_eq!(line.params, vec!["#rickastley", "Never gonna give you up!"]);
+            }
+            Err(e) => {
+                println!("A parsing error occured: {e}");
+                assert!(false);
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_full() {
+        let msg = "@id=123;name=rick :nick!user@host.tmi.twitch.tv PRIVMSG #rickastley :Never gonna give you up!\r\n";
+        match parse(msg) {
+            Ok(mut x) => {
+                assert_eq!(x.len(), 1);
+                let line = x.pop_front().unwrap();
+
+                assert_eq!(
+                    line.tags,
+                    hashmap! {
+                        String::from("id") => String::from("123"),
+                        String::from("name") => String::from("rick"),
+                    }
+                );
+                assert_eq!(
+                    line.source,
+                    Some(String::from(":nick!user@host.tmi.twitch.tv"))
+                );
+                assert_eq!(line.command, "PRIVMSG");
+                assert_eq!(line.params, vec!["#rickastley", "Never gonna give you up!"]);
+            }
+            Err(e) => {
+                println!("A parsing error occured: {e}");
+                return;
+            }
+        };
+    }
+
+    #[test]
+    fn test_readme_example() {
+        let msg = "@id=123;name=rick :nick!user@host.tmi.twitch.tv PRIVMSG #rickastley :Never gonna give you up!\r\n";
+        match parse(msg) {
+            Ok(mut x) => {
+                println!("{x:?}");
+                assert_eq!(x.len(), 1);
+                let line = x.pop_front().unwrap();
+
+                assert_eq!(&line.tags["id"], "123");
+                if line.source.is_some() {
+                    assert_eq!(line.source.unwrap(), ":nick!user@host.tmi.twitch.tv");
+                }
+                assert_eq!(line.command, "PRIVMSG");
+                assert_eq!(line.params[0], "#rickastley");
+                assert_eq!(line.params[1], "Never gonna give you up!");
+            }
+            Err(e) => {
+                println!("A parsing error occured: {e}");
+                assert!(false);
+            }
+        };
     }
 
     #[test]
@@ -315,4 +1076,877 @@ mod test_lib {
             }
         }
     }
+
+    #[test]
+    fn test_tolerant_tags_after_source() {
+        let msg =
+            ":nick!user@host @id=123;name=rick PRIVMSG #rickastley :Never gonna give you up!\r\n";
+        let config = ParseConfig {
+            tolerant: true,
+            ..Default::default()
+        };
+
+        match parse_with(msg, &config) {
+            Ok(mut x) => {
+                assert_eq!(x.len(), 1);
+                let line = x.pop_front().unwrap();
+
+                assert_eq!(
+                    line.tags,
+                    hashmap! {
+                        String::from("id") => String::from("123"),
+                        String::from("name") => String::from("rick"),
+                    }
+                );
+                assert_eq!(line.source, Some(String::from(":nick!user@host")));
+                assert_eq!(line.command, "PRIVMSG");
+                assert_eq!(line.params, vec!["#rickastley", "Never gonna give you up!"]);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tags_after_source_warning() {
+        let msg = "PING :irc.example.com\r\n:nick!user@host @id=123;name=rick PRIVMSG #rickastley :Never gonna give you up!\r\n";
+        let config = ParseConfig {
+            tolerant: true,
+            ..Default::default()
+        };
+
+        match parse_with_warnings(msg, &config) {
+            Ok((lines, warnings)) => {
+                assert_eq!(lines.len(), 2);
+                assert_eq!(&lines[1].tags["id"], "123");
+                assert_eq!(&lines[1].tags["name"], "rick");
+                assert_eq!(lines[1].source, Some(String::from(":nick!user@host")));
+
+                assert_eq!(warnings.len(), 1);
+                assert_eq!(warnings[0].0, 1);
+                assert_eq!(warnings[0].1.kind(), &ParseErrorKind::TagsAfterSource);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        let msg = "@id=123 :nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n";
+        let (_, warnings) = parse_with_warnings(msg, &config).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_strict_tags_after_source() {
+        let msg = ":nick!user@host @id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n";
+
+        match parse(msg) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+
+                assert_eq!(line.tags, HashMap::new());
+                assert_eq!(line.command, "@id=123");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        let msg = "PING x\r\n@id=123;name=rick :nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n";
+        match parse_spans(msg) {
+            Ok(x) => {
+                assert_eq!(x.len(), 2);
+                let spans = &x[1];
+
+                assert_eq!(spans.tags, Some(9..25));
+                assert_eq!(spans.source, Some(26..41));
+                assert_eq!(spans.command, 42..49);
+                assert_eq!(spans.params, vec![50..61, 63..87]);
+
+                assert_eq!(&msg[9..25], "id=123;name=rick");
+                assert_eq!(&msg[26..41], ":nick!user@host");
+                assert_eq!(&msg[42..49], "PRIVMSG");
+                assert_eq!(&msg[50..61], "#rickastley");
+                assert_eq!(&msg[63..87], "Never gonna give you up!");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tags_too_long() {
+        let tags = format!("@id={}", "a".repeat(8186));
+        assert_eq!(tags.len() + 1, 8191);
+
+        match parse(&format!(
+            "{tags} PRIVMSG #rickastley :Never gonna give you up!\r\n"
+        )) {
+            Ok(x) => assert_eq!(x.len(), 1),
+            Err(e) => panic!("A parsing error occured: {e}"),
+        }
+
+        match parse(&format!(
+            "{tags}a PRIVMSG #rickastley :Never gonna give you up!\r\n"
+        )) {
+            Ok(_) => panic!("Expected a parsing error"),
+            Err(e) => assert_eq!(e.kind(), &ParseErrorKind::TagsTooLong { len: 8192 }),
+        }
+    }
+
+    #[test]
+    fn test_reparse_equals() {
+        let msgs = [
+            "PRIVMSG #rickastley :Never gonna give you up!\r\n",
+            "@id=123;name=rick :nick!user@host.tmi.twitch.tv PRIVMSG #rickastley :Never gonna give you up!\r\n",
+            ":irc.example.com 001 rick :Welcome to the network, rick\r\n",
+            ":nick!user@host JOIN #rickastley\r\n",
+            ":nick!user@host MODE #rickastley +o rick\r\n",
+            "PING :irc.example.com\r\n",
+            "PRIVMSG #rickastley ::)\r\n",
+            "PRIVMSG #rickastley :\r\n",
+        ];
+
+        for msg in msgs {
+            assert!(reparse_equals(msg), "{msg:?} did not survive a round trip");
+        }
+    }
+
+    #[test]
+    fn test_trailing_only() {
+        match parse("PING :irc.example.com\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.params, vec!["irc.example.com"]);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_colon_in_middle_param() {
+        match parse("PRIVMSG #rick:astley :Never gonna give you up!\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(
+                    line.params,
+                    vec!["#rick:astley", "Never gonna give you up!"]
+                );
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_twitch_privmsg() {
+        let msg = "@badge-info=subscriber/12;badges=subscriber/12,premium/1;client-nonce=abc123;color=#FF0000;display-name=RickAstley;emotes=25:0-4,12-16/1902:6-10;first-msg=0;flags=;id=b34ccfc7-4977-403a-8a94-33c6bac34fb8;mod=0;returning-chatter=0;room-id=1337;subscriber=1;tmi-sent-ts=1507246572675;turbo=1;user-id=1337;user-type=;msg-param-sub-plan-name=Channel\\sSubscription\\s(rick);system-msg=Never\\sgonna\\sgive\\syou\\sup\\:\\snever;url=https://example.com/?a=1&b=2 :rickastley!rickastley@rickastley.tmi.twitch.tv PRIVMSG #rickastley :Kappa Keepo Kappa\r\n";
+        match parse(msg) {
+            Ok(mut x) => {
+                assert_eq!(x.len(), 1);
+                let line = x.pop_front().unwrap();
+
+                assert_eq!(line.tags.len(), 20);
+                assert_eq!(&line.tags["badge-info"], "subscriber/12");
+                assert_eq!(&line.tags["badges"], "subscriber/12,premium/1");
+                assert_eq!(&line.tags["color"], "#FF0000");
+                assert_eq!(&line.tags["display-name"], "RickAstley");
+                assert_eq!(&line.tags["emotes"], "25:0-4,12-16/1902:6-10");
+                assert_eq!(&line.tags["flags"], "");
+                assert_eq!(&line.tags["user-type"], "");
+                assert_eq!(&line.tags["tmi-sent-ts"], "1507246572675");
+                assert_eq!(
+                    &line.tags["msg-param-sub-plan-name"],
+                    "Channel Subscription (rick)"
+                );
+                assert_eq!(&line.tags["system-msg"], "Never gonna give you up; never");
+                assert_eq!(&line.tags["url"], "https://example.com/?a=1&b=2");
+                assert_eq!(
+                    line.source,
+                    Some(String::from(
+                        ":rickastley!rickastley@rickastley.tmi.twitch.tv"
+                    ))
+                );
+                assert_eq!(line.command, "PRIVMSG");
+                assert_eq!(line.params, vec!["#rickastley", "Kappa Keepo Kappa"]);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_bare_tags() {
+        match parse("@draft/bot;id=123; PRIVMSG #rickastley :Never gonna give you up!\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+
+                assert_eq!(
+                    line.tags,
+                    hashmap! {
+                        String::from("draft/bot") => String::new(),
+                        String::from("id") => String::from("123"),
+                    }
+                );
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_whitespace_trailing() {
+        let msg = "PRIVMSG #rickastley :   \r\nPRIVMSG #rickastley :  Never  gonna  \r\n";
+        match parse(msg) {
+            Ok(mut x) => {
+                let l1 = x.pop_front().unwrap();
+                let l2 = x.pop_front().unwrap();
+
+                assert_eq!(l1.params, vec!["#rickastley", "   "]);
+                assert_eq!(l2.params, vec!["#rickastley", "  Never  gonna  "]);
+                assert_eq!(l1.to_string(), "PRIVMSG #rickastley :   ");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_params() {
+        let config = ParseConfig {
+            max_params: Some(15),
+            ..Default::default()
+        };
+        let middle: Vec<String> = (1..=14).map(|x| x.to_string()).collect();
+        let middle = middle.join(" ");
+
+        match parse_with(&format!("CMD {middle} 15\r\n"), &config) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.params.len(), 15);
+                assert_eq!(line.params[14], "15");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        match parse_with(&format!("CMD {middle} 15 16 :17\r\n"), &config) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.params.len(), 15);
+                assert_eq!(line.params[13], "14");
+                assert_eq!(line.params[14], "15 16 :17");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        match parse_with(&format!("CMD {middle} :15 16\r\n"), &config) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.params.len(), 15);
+                assert_eq!(line.params[14], "15 16");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_max_params() {
+        let middle: Vec<String> = (1..=16).map(|x| x.to_string()).collect();
+
+        match parse(&format!("CMD {}\r\n", middle.join(" "))) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.params, middle);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_into_io_error() {
+        let e: std::io::Error = ParseError::new("err").into();
+
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(e.to_string(), "err");
+        assert!(e.get_ref().unwrap().is::<ParseError>());
+    }
+
+    #[test]
+    fn test_parse_into_reuse() {
+        let mut lines: VecDeque<Line> = VecDeque::new();
+
+        parse_into(
+            "@id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n",
+            &mut lines,
+        )
+        .unwrap();
+        assert_eq!(lines.len(), 1);
+
+        parse_into(
+            "@id=456 PRIVMSG #rickastley :Never gonna let you down!\r\n",
+            &mut lines,
+        )
+        .unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(&lines[0].tags["id"], "123");
+        assert_eq!(&lines[1].tags["id"], "456");
+
+        lines.clear();
+        parse_into("PING :irc.example.com\r\n", &mut lines).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].command, "PING");
+    }
+
+    #[test]
+    fn test_normalize_command() {
+        let config = ParseConfig {
+            normalize_command: true,
+            ..Default::default()
+        };
+
+        match parse_with(
+            "privmsg #rickastley :Never gonna give you up!\r\n:server 001 rick :Hi\r\n",
+            &config,
+        ) {
+            Ok(mut x) => {
+                assert_eq!(x.pop_front().unwrap().command, "PRIVMSG");
+                assert_eq!(x.pop_front().unwrap().command, "001");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        match parse("privmsg #rickastley :Never gonna give you up!\r\n") {
+            Ok(mut x) => {
+                assert_eq!(x.pop_front().unwrap().command, "privmsg");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_tag_value_len() {
+        let config = ParseConfig {
+            max_tag_value_len: Some(5),
+            ..Default::default()
+        };
+
+        match parse_with(
+            "@id=12345;a\\sb PRIVMSG #rickastley :Never gonna give you up!\r\n",
+            &config,
+        ) {
+            Ok(x) => assert_eq!(x.len(), 1),
+            Err(e) => panic!("A parsing error occured: {e}"),
+        }
+
+        match parse_with(
+            "@name=rick;id=123456 PRIVMSG #rickastley :Never gonna give you up!\r\n",
+            &config,
+        ) {
+            Ok(_) => panic!("Expected a parsing error"),
+            Err(e) => assert_eq!(
+                e.kind(),
+                &ParseErrorKind::TagValueTooLong { key: "id".into() }
+            ),
+        }
+    }
+
+    #[test]
+    fn test_max_tag_value_len_raw() {
+        for raw_tag_values in [false, true] {
+            let config = ParseConfig {
+                max_tag_value_len: Some(3),
+                raw_tag_values,
+                ..Default::default()
+            };
+
+            // Both values are 3 bytes once unescaped.
+            assert!(parse_with("@a=\\s\\s\\s;b=abc PING :x\r\n", &config).is_ok());
+
+            let e = parse_with("@a=\\s\\s\\s\\s PING :x\r\n", &config).unwrap_err();
+            assert_eq!(
+                e.kind(),
+                &ParseErrorKind::TagValueTooLong { key: "a".into() }
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        let msg = format!(
+            "@id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n\r\n@{} PRIVMSG #rickastley :Never gonna let you down!\r\n@id=456 PRIVMSG #rickastley :Never gonna run around\r\n",
+            "a".repeat(8192)
+        );
+        let (lines, errors) = parse_partial(&msg);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(&lines[0].tags["id"], "123");
+        assert_eq!(&lines[1].tags["id"], "456");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 2);
+        assert_eq!(
+            errors[0].1.details,
+            "tag section is too long (8194 bytes, maximum is 8191)"
+        );
+    }
+
+    #[test]
+    fn test_parse_with_raw() {
+        let long = format!("@{} PING :x", "a".repeat(8192));
+        let segments = [
+            "@id=123;name=rick :nick!user@host PRIVMSG #rickastley :Never gonna give you up!",
+            long.as_str(),
+            "PING :irc.example.com",
+        ];
+        let lines = parse_with_raw(&format!("{}\r\n\r\n", segments.join("\r\n")));
+
+        assert_eq!(lines.len(), 3);
+        for ((raw, _), segment) in lines.iter().zip(segments) {
+            assert_eq!(raw, segment);
+        }
+        assert_eq!(lines[0].1.as_ref().unwrap().command, "PRIVMSG");
+        assert!(lines[1].1.is_err());
+        assert_eq!(lines[2].1.as_ref().unwrap().params, vec!["irc.example.com"]);
+    }
+
+    #[test]
+    fn test_strict_tag_escapes() {
+        let config = ParseConfig {
+            strict_tag_escapes: true,
+            ..Default::default()
+        };
+
+        match parse_with("@a=b\\sc PING :x\r\n", &config) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.tags["a"], "b c");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        let e = parse_with("@a=b\\xc PING :x\r\n", &config).unwrap_err();
+        assert_eq!(
+            e.kind(),
+            &ParseErrorKind::BadTagEscape { seq: "\\x".into() }
+        );
+    }
+
+    #[test]
+    fn test_lenient_tag_escapes() {
+        match parse("@a=b\\sc;d=e\\xf PING :x\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.tags["a"], "b c");
+                assert_eq!(line.tags["d"], "exf");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_parse_small_single() {
+        match super::parse_small("PRIVMSG #rickastley :Never gonna give you up!\r\n") {
+            Ok(x) => {
+                assert!(!x.spilled());
+                assert_eq!(x.len(), 1);
+                assert_eq!(x[0].command, "PRIVMSG");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_parse_small_multiline() {
+        match super::parse_small("PING :a\r\nPING :b\r\nPING :c\r\n") {
+            Ok(x) => {
+                assert!(x.spilled());
+                let params: Vec<&str> = x.iter().map(|x| x.params[0].as_str()).collect();
+                assert_eq!(params, vec!["a", "b", "c"]);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_raw_tag_values() {
+        let config = ParseConfig {
+            raw_tag_values: true,
+            ..Default::default()
+        };
+        let msg =
+            r"@a=b\sc\xd\;e=\\\:f\ :nick!user@host PRIVMSG #rickastley :Never gonna give you up!";
+
+        match parse_with(msg, &config) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.tags["a"], r"b\sc\xd\");
+                assert_eq!(line.to_string_raw_tags(), msg);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        for msg in [r"@a=;b=\q PING :x", r"@a;b=\q PING :x"] {
+            let line = parse_with(msg, &config).unwrap().pop_front().unwrap();
+            assert_eq!(line.to_string_raw_tags(), msg);
+        }
+    }
+
+    #[test]
+    fn test_normalize_tag_vendors() {
+        let config = ParseConfig {
+            normalize_tag_vendors: true,
+            ..Default::default()
+        };
+
+        match parse_with(
+            "@Example.COM/Foo=a;+Example.COM/Bar=b;Baz=c PING :x\r\n",
+            &config,
+        ) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(
+                    line.tags,
+                    hashmap! {
+                        String::from("example.com/Foo") => String::from("a"),
+                        String::from("+example.com/Bar") => String::from("b"),
+                        String::from("Baz") => String::from("c"),
+                    }
+                );
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_normalize_tag_vendors() {
+        match parse("@Example.COM/Foo=a PING :x\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(&line.tags["Example.COM/Foo"], "a");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_borrowed_into() {
+        let msg = "@id=123;name=rick\\sastley;bare :nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\nPING :irc.example.com\r\n";
+        let mut borrowed = Vec::new();
+        parse_borrowed_into(msg, &mut borrowed).unwrap();
+        let lines = parse(msg).unwrap();
+
+        assert_eq!(borrowed.len(), lines.len());
+        for (b, line) in borrowed.iter().zip(lines) {
+            let tags: HashMap<String, String> = b
+                .tags
+                .iter()
+                .map(|(k, v)| (k.to_string(), crate::tags::unescape_value(v)))
+                .collect();
+
+            assert_eq!(tags, line.tags);
+            assert_eq!(b.source, line.source.as_deref());
+            assert_eq!(b.command, line.command);
+            assert_eq!(b.params, line.params);
+        }
+
+        assert_eq!(
+            borrowed[0].tags,
+            vec![("id", "123"), ("name", "rick\\sastley"), ("bare", "")]
+        );
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let msg = "PING :irc.example.com\r\n:nick!user@host PRIVMSG #rickastley :Never gonna give\r\n:nick!user@host JOIN #rickastley\r\n:nick!user@host privmsg #rickastley :you up!\r\n";
+
+        match parse_filter(msg, &["PRIVMSG"]) {
+            Ok(x) => {
+                let params: Vec<&str> = x.iter().map(|x| x.params[1].as_str()).collect();
+                assert_eq!(params, vec!["Never gonna give", "you up!"]);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_filter_none() {
+        match parse_filter("PING :irc.example.com\r\n", &["PRIVMSG", "NOTICE"]) {
+            Ok(x) => {
+                assert!(x.is_empty());
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_strict_tag_keys() {
+        let config = ParseConfig {
+            strict_tag_keys: true,
+            ..Default::default()
+        };
+
+        match parse_with("@+example.com/foo-bar=a;id=1;bare PING :x\r\n", &config) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.tags.len(), 3);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        for (msg, key) in [
+            ("@foo_bar=a PING :x\r\n", "foo_bar"),
+            ("@=a PING :x\r\n", ""),
+            ("@a/=b PING :x\r\n", "a/"),
+        ] {
+            let e = parse_with(msg, &config).unwrap_err();
+            assert_eq!(e.kind(), &ParseErrorKind::BadTagKey { key: key.into() });
+        }
+    }
+
+    #[test]
+    fn test_lenient_tag_keys() {
+        match parse("@foo_bar=a PING :x\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(&line.tags["foo_bar"], "a");
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_ok_iter() {
+        let msg = format!(
+            "PING :a\r\n@{} PING :b\r\nPRIVMSG #rickastley :c\r\n",
+            "a".repeat(8192)
+        );
+        let params: Vec<String> = parse_ok_iter(&msg)
+            .map(|mut x| x.params.remove(0))
+            .collect();
+
+        assert_eq!(params, vec!["a", "#rickastley"]);
+    }
+
+    #[test]
+    fn test_no_params() {
+        match parse(":nick!user@host QUIT\r\nPING\r\n") {
+            Ok(x) => {
+                assert_eq!(x[0].command, "QUIT");
+                assert_eq!(x[0].source.as_deref(), Some(":nick!user@host"));
+                assert!(x[0].params.is_empty());
+                assert_eq!(x[1].command, "PING");
+                assert!(x[1].params.is_empty());
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_command() {
+        for msg in [
+            "@id=123",
+            "@id=123 ",
+            ":nick!user@host",
+            ":nick!user@host ",
+            " PING",
+        ] {
+            let e = parse(msg).unwrap_err();
+            assert_eq!(e.details, "line has no command");
+        }
+    }
+
+    #[test]
+    fn test_fuzz_parse() {
+        let fixtures: [&[u8]; 14] = [
+            b"",
+            b" ",
+            b"@",
+            b":",
+            b"PING",
+            b"@a",
+            b":src",
+            b"@a :src",
+            b"PRIVMSG:hi",
+            b"@\xe9\xff :\xff PING",
+            b"@a=\\ PING :x\\",
+            b"\r\n\r\n\r",
+            b"@a=b;;=;c :nick!user@host  PRIVMSG  #a  :",
+            b"\xf0\x9f\x91\x8d :\xf0\x9f\x91\x8d \xf0\x9f\x91\x8d",
+        ];
+        for data in fixtures {
+            fuzz_parse(data);
+        }
+
+        // Feed pseudorandom bytes, weighted towards IRC syntax.
+        let alphabet = b"@:; =\\\r\nab#\xc3\xa9\xff";
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..2000 {
+            let mut data = Vec::new();
+            for _ in 0..(state % 48) {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                data.push(alphabet[(state % alphabet.len() as u64) as usize]);
+            }
+            fuzz_parse(&data);
+        }
+
+        assert_eq!(fuzz_parse(b"PING :a\r\nPING\r\n@\r\n"), 2);
+    }
+
+    #[test]
+    fn test_colon_after_command() {
+        let e = parse("PRIVMSG:hi\r\n").unwrap_err();
+        assert_eq!(
+            e.details,
+            r#"command "PRIVMSG:hi" contains a colon (is the space before the trailing param missing?)"#
+        );
+
+        let e = parse(":nick!user@host :hi\r\n").unwrap_err();
+        assert_eq!(e.details, "line has no command");
+    }
+
+    #[test]
+    fn test_tolerant_colon_after_command() {
+        let config = ParseConfig {
+            tolerant: true,
+            ..Default::default()
+        };
+
+        match parse_with("PRIVMSG:hi there\r\nPRIVMSG :hi there\r\n", &config) {
+            Ok(x) => {
+                for line in x {
+                    assert_eq!(line.command, "PRIVMSG");
+                    assert_eq!(line.params, vec!["hi there"]);
+                }
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_space_before_trailing() {
+        match parse("PRIVMSG :hi\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.command, "PRIVMSG");
+                assert_eq!(line.params, vec!["hi"]);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_strip_source_colon() {
+        let config = ParseConfig {
+            strip_source_colon: true,
+            ..Default::default()
+        };
+
+        match parse_with(
+            ":nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n",
+            &config,
+        ) {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.source.as_deref(), Some("nick!user@host"));
+                assert_eq!(
+                    line.to_string(),
+                    ":nick!user@host PRIVMSG #rickastley :Never gonna give you up!"
+                );
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_keep_source_colon() {
+        match parse(":nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n") {
+            Ok(mut x) => {
+                let line = x.pop_front().unwrap();
+                assert_eq!(line.source.as_deref(), Some(":nick!user@host"));
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_lines() {
+        let config = ParseConfig {
+            max_lines: Some(2),
+            ..Default::default()
+        };
+
+        match parse_with("PING :a\r\n\r\nPING :b\r\n", &config) {
+            Ok(x) => {
+                assert_eq!(x.len(), 2);
+            }
+            Err(e) => {
+                panic!("A parsing error occured: {e}");
+            }
+        }
+
+        let mut out = VecDeque::new();
+        let e = crate::parse_into_with("PING :a\r\nPING :b\r\nPING :c\r\n", &config, &mut out)
+            .unwrap_err();
+        assert_eq!(e.kind(), &ParseErrorKind::TooManyLines { max: 2 });
+        assert_eq!(out.len(), 2);
+    }
 }
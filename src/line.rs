@@ -28,9 +28,33 @@
 // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
 // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::collections::HashMap;
+use crate::tags::{escape_value, is_valid_key};
+use crate::time::parse_iso8601;
+use crate::{
+    CaseMapping, ChannelVisibility, Names, NamesEntry, ParseError, Severity, Source, StandardReply,
+    TypingState,
+};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// A struct representing a parsed line.
+///
+/// A line is only valid if it has a non-empty command. The [`Default`]
+/// line has an empty command, and so must have one set before it is
+/// sent. [`Line::try_to_string`] can be used to check this when
+/// formatting.
+///
+/// # Notice
+/// As of v0.3.0, lines also record the form they were written in, in
+/// [`bare_tags`](Line::bare_tags) and
+/// [`has_trailing`](Line::has_trailing). `has_trailing` is compared by
+/// [`PartialEq`], so `PING :x` and `PING x` no longer compare equal,
+/// even though their params are the same. `bare_tags` is not, as IRCv3
+/// defines `@a` and `@a=` to be the same. Lines should be created with
+/// [`Line::new`] rather than a struct literal, so that new fields like
+/// these don't break your code. [`Line::push_param`] and
+/// [`Line::push_trailing`] return a [`Result`], as they reject params
+/// that would change the line when formatted.
 #[derive(Debug, Clone, Default)]
 pub struct Line {
     /// This line's tags. This will be an empty hashmap if there are
@@ -47,6 +71,42 @@ pub struct Line {
     /// Any parameters passed to the command. This will be an empty
     /// vector if there are none.
     pub params: Vec<String>,
+
+    /// The keys of tags which were sent bare (`key` rather than `key=`).
+    /// These are formatted without the `=` while their value is empty;
+    /// any other tag with an empty value keeps it. This only affects
+    /// formatting, so it is ignored when comparing lines. Keys whose tag
+    /// is missing from [`tags`](Line::tags), or has a value, are ignored.
+    /// [`Line::set_tag`] and similar methods keep it up to date.
+    pub bare_tags: HashSet<String>,
+
+    /// Whether the last param is a trailing param (one that was sent
+    /// with a leading `:`). Trailing params are always formatted with
+    /// the `:`, even when it isn't needed. This is compared by
+    /// [`PartialEq`], along with the other fields.
+    pub has_trailing: bool,
+}
+
+/// Options controlling how a line is written as a raw IRC message.
+struct RawFormat {
+    /// Whether to always write the last param as a trailing param.
+    always_trailing: bool,
+
+    /// Whether to escape tag values.
+    escape_tags: bool,
+
+    /// Whether to write tags in [`Line::bare_tags`] without an `=`.
+    bare_tags: bool,
+}
+
+impl RawFormat {
+    /// The format used by [`Line`]'s [`Display`](std::fmt::Display)
+    /// implementation.
+    const DISPLAY: Self = Self {
+        always_trailing: false,
+        escape_tags: true,
+        bare_tags: true,
+    };
 }
 
 impl Line {
@@ -55,8 +115,8 @@ impl Line {
     ///
     /// # Arguments
     /// - `tags` - This line's tags.
-    /// = `source` - This line's source, or [`None`] if not to be
-    /// provided.
+    /// - `source` - This line's source, or [`None`] if not to be
+    ///   provided.
     /// - `command` - This line's command.
     /// - `params` - Any parameters passed to the command.
     ///
@@ -93,6 +153,3334 @@ impl Line {
             source,
             command: command.to_string(),
             params,
+            bare_tags: HashSet::new(),
+            has_trailing: false,
+        }
+    }
+
+    /// Gets every channel referenced by this line's params. Comma
+    /// separated lists (such as those in `JOIN`) are split into their
+    /// individual channels. A trailing param is usually free text, so it
+    /// is skipped unless it is this line's only param (as in
+    /// `JOIN :#channel`) or the channel of an `INVITE`.
+    ///
+    /// # Arguments
+    /// - `chantypes` - The characters channel names may start with (the
+    ///   `CHANTYPES` ISUPPORT token, usually `"#&"`).
+    ///
+    /// # Returns
+    /// - [`Vec<&str>`] - All referenced channels, in the order they
+    ///   appear.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("JOIN #rickastley,&never\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.channels("#&"), vec!["#rickastley", "&never"]);
+    ///
+    /// let line = ircparser::parse("PRIVMSG #rickastley :#never\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.channels("#&"), vec!["#rickastley"]);
+    /// ```
+    ///
+    pub fn channels(&self, chantypes: &str) -> Vec<&str> {
+        let skip_trailing = self.has_trailing
+            && self.params.len() > 1
+            && !self.command.eq_ignore_ascii_case("INVITE");
+        let params = match self.params.split_last() {
+            Some((_, rest)) if skip_trailing => rest,
+            _ => &self.params[..],
+        };
+
+        params
+            .iter()
+            .flat_map(|p| p.split(','))
+            .filter(|p| p.starts_with(|c| chantypes.contains(c)) && !p.contains(' '))
+            .collect()
+    }
+
+    /// Merges the given tags into this line's tags. Where a tag exists
+    /// in both, the value from `other` wins.
+    ///
+    /// # Arguments
+    /// - `other` - The tags to merge in.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// let mut line = ircparser::parse("@id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n")
+    ///     .unwrap()
+    ///     .pop_front()
+    ///     .unwrap();
+    ///
+    /// let mut tags: HashMap<String, String> = HashMap::new();
+    /// tags.insert("id".to_string(), "456".to_string());
+    /// line.merge_tags(&tags);
+    ///
+    /// assert_eq!(&line.tags["id"], "456");
+    /// ```
+    ///
+    pub fn merge_tags(&mut self, other: &HashMap<String, String>) {
+        for (k, v) in other {
+            self.bare_tags.remove(k);
+            self.tags.insert(k.clone(), v.clone());
+        }
+    }
+
+    /// Creates a copy of this line with the given tags merged in, as per
+    /// [`Line::merge_tags`].
+    ///
+    /// # Arguments
+    /// - `tags` - The tags to merge in.
+    ///
+    /// # Returns
+    /// - [`Line`] - The new [`Line`] instance.
+    ///
+    pub fn with_tags(&self, tags: &HashMap<String, String>) -> Self {
+        let mut line = self.clone();
+        line.merge_tags(tags);
+        line
+    }
+
+    /// Decomposes this line into a [`StandardReply`], if it is one.
+    ///
+    /// # Returns
+    /// - [`Option<StandardReply>`] - The standard reply, or [`None`] if
+    ///   this line is not a `FAIL`, `WARN`, or `NOTE` command.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::Severity;
+    ///
+    /// let msg = "FAIL JOIN CHANNEL_FULL #rickastley :Never gonna let you in\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    /// let reply = line.standard_reply().unwrap();
+    ///
+    /// assert_eq!(reply.severity, Severity::Fail);
+    /// assert_eq!(reply.command, "JOIN");
+    /// assert_eq!(reply.code, "CHANNEL_FULL");
+    /// assert_eq!(reply.context, vec!["#rickastley"]);
+    /// assert_eq!(reply.description, "Never gonna let you in");
+    /// ```
+    ///
+    pub fn standard_reply(&self) -> Option<StandardReply> {
+        let severity = match self.command.to_ascii_uppercase().as_str() {
+            "FAIL" => Severity::Fail,
+            "WARN" => Severity::Warn,
+            "NOTE" => Severity::Note,
+            _ => return None,
+        };
+
+        if self.params.len() < 3 {
+            return None;
+        }
+
+        let last = self.params.len() - 1;
+        Some(StandardReply {
+            severity,
+            command: self.params[0].clone(),
+            code: self.params[1].clone(),
+            context: self.params[2..last].to_vec(),
+            description: self.params[last].clone(),
+        })
+    }
+
+    /// Gets the nick from this line's source, if there is one.
+    fn source_nick(&self) -> Option<&str> {
+        let source = self.source.as_ref()?;
+        let source = source.strip_prefix(':').unwrap_or(source);
+        source.split(['!', '@']).next()
+    }
+
+    /// Renders this line as a compact, single-line string suitable for
+    /// logging. This shows the command, the source's nick (or `*` if
+    /// there is no source), and the params, with the last param quoted
+    /// and truncated to 32 characters.
+    ///
+    /// # Returns
+    /// - [`String`] - The compact representation of this line.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":rick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.to_debug_string(),
+    ///     "PRIVMSG rick #rickastley \"Never gonna give you up!\""
+    /// );
+    /// ```
+    ///
+    pub fn to_debug_string(&self) -> String {
+        let mut parts = vec![
+            self.command.clone(),
+            self.source_nick().unwrap_or("*").to_string(),
+        ];
+
+        if let Some((last, middle)) = self.params.split_last() {
+            parts.extend(middle.iter().cloned());
+
+            let mut trailing: String = last.chars().take(32).collect();
+            if trailing.len() < last.len() {
+                trailing.push_str("...");
+            }
+            parts.push(format!("{trailing:?}"));
+        }
+
+        parts.join(" ")
+    }
+
+    /// Parses this line as an `RPL_NAMREPLY` (`353`) reply.
+    ///
+    /// # Arguments
+    /// - `prefixes` - The prefix symbols members may have (the symbols
+    ///   in the `PREFIX` ISUPPORT token, usually `"~&@%+"`).
+    ///
+    /// # Returns
+    /// - [`Option<Names>`] - The parsed names, or [`None`] if this line
+    ///   is not a `353` reply.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":server 353 me = #rickastley :@rick +nick!user@host\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    /// let names = line.names("~&@%+").unwrap();
+    ///
+    /// assert_eq!(names.channel, "#rickastley");
+    /// assert_eq!(names.visibility, Some(ircparser::ChannelVisibility::Public));
+    /// assert_eq!(names.members[0].prefixes, vec!['@']);
+    /// assert_eq!(names.members[0].source.nick, "rick");
+    /// assert_eq!(names.members[1].source.host.as_deref(), Some("host"));
+    /// ```
+    ///
+    pub fn names(&self, prefixes: &str) -> Option<Names> {
+        if self.command != "353" || self.params.len() < 3 {
+            return None;
+        }
+
+        let last = self.params.len() - 1;
+        let members = self.params[last]
+            .split(' ')
+            .filter(|x| !x.is_empty())
+            .map(|x| {
+                let nick = x.trim_start_matches(|c| prefixes.contains(c));
+                NamesEntry {
+                    prefixes: x[..x.len() - nick.len()].chars().collect(),
+                    source: Source::parse(nick),
+                }
+            })
+            .collect();
+
+        // Some servers leave out the visibility symbol.
+        let visibility = match last {
+            3 => ChannelVisibility::from_symbol(&self.params[1]),
+            _ => None,
+        };
+
+        Some(Names {
+            channel: self.params[last - 1].clone(),
+            visibility,
+            members,
+        })
+    }
+
+    /// Gets this line's params as string slices, for easy comparison
+    /// against string literals.
+    ///
+    /// # Returns
+    /// - [`Vec<&str>`] - This line's params.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("PRIVMSG #rickastley :Never gonna give you up!\r\n")
+    ///     .unwrap()
+    ///     .pop_front()
+    ///     .unwrap();
+    ///
+    /// match line.param_refs().as_slice() {
+    ///     ["#rickastley", text] => assert_eq!(*text, "Never gonna give you up!"),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    pub fn param_refs(&self) -> Vec<&str> {
+        self.params.iter().map(|x| x.as_str()).collect()
+    }
+
+    /// Gets the account name of this line's source. This checks the
+    /// `account` tag (from the `account-tag` capability), then, for
+    /// `JOIN` commands, the account param (from the `extended-join`
+    /// capability).
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The account name, or [`None`] if it isn't
+    ///   known or the source isn't logged in (`*`).
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":rick!user@host JOIN #rickastley rick :Rick Astley\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.account_name(), Some("rick"));
+    /// ```
+    ///
+    pub fn account_name(&self) -> Option<&str> {
+        let account = match self.tags.get("account") {
+            Some(x) => x.as_str(),
+            None if self.command.eq_ignore_ascii_case("JOIN") && self.params.len() >= 3 => {
+                self.params[1].as_str()
+            }
+            None => return None,
+        };
+
+        match account {
+            "*" => None,
+            x => Some(x),
+        }
+    }
+
+    /// Replaces this line's target (its first param) with a new one.
+    /// This only affects commands which take a target (`PRIVMSG`,
+    /// `NOTICE`, `TAGMSG`, `JOIN`, `PART`, `KICK`, `MODE`, `TOPIC`, and
+    /// `NAMES`), and does nothing for any other command.
+    ///
+    /// # Arguments
+    /// - `new_target` - The new target.
+    ///
+    /// # Example
+    /// ```
+    /// let mut line = ircparser::parse("PRIVMSG #rickastley :Never gonna give you up!\r\n")
+    ///     .unwrap()
+    ///     .pop_front()
+    ///     .unwrap();
+    /// line.rewrite_target("#rick");
+    ///
+    /// assert_eq!(line.params[0], "#rick");
+    /// ```
+    ///
+    pub fn rewrite_target(&mut self, new_target: &str) {
+        const TARGETED: [&str; 9] = [
+            "PRIVMSG", "NOTICE", "TAGMSG", "JOIN", "PART", "KICK", "MODE", "TOPIC", "NAMES",
+        ];
+
+        if !TARGETED
+            .iter()
+            .any(|x| self.command.eq_ignore_ascii_case(x))
+        {
+            return;
+        }
+
+        if let Some(target) = self.params.first_mut() {
+            *target = new_target.to_string();
         }
     }
+
+    /// Renders this line as a raw message which is safe to display in a
+    /// terminal. Control characters (such as `\x01` or `\x1b`) are
+    /// rendered as `\xNN` escape sequences.
+    ///
+    /// # Returns
+    /// - [`String`] - The escaped message.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = "PRIVMSG #rickastley :\x01ACTION dances\x01\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.to_escaped_log(), r"PRIVMSG #rickastley :\x01ACTION dances\x01");
+    /// ```
+    ///
+    pub fn to_escaped_log(&self) -> String {
+        let mut escaped = String::new();
+
+        for c in self.to_string().chars() {
+            if c.is_control() {
+                escaped.push_str(&format!("\\x{:02x}", c as u32));
+            } else {
+                escaped.push(c);
+            }
+        }
+
+        escaped
+    }
+
+    /// Gets the invited nick and channel from an `INVITE` command or an
+    /// `RPL_INVITING` (`341`) reply. For `INVITE`, the inviter is this
+    /// line's source.
+    ///
+    /// # Returns
+    /// - [`Option<(String, String)>`] - The invited nick and the channel,
+    ///   or [`None`] if this line is not an invite.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":rick!user@host INVITE nick #rickastley\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.invite_info(),
+    ///     Some(("nick".to_string(), "#rickastley".to_string()))
+    /// );
+    /// ```
+    ///
+    pub fn invite_info(&self) -> Option<(String, String)> {
+        let params = if self.command.eq_ignore_ascii_case("INVITE") {
+            self.params.get(0..2)?
+        } else if self.command == "341" {
+            self.params.get(1..3)?
+        } else {
+            return None;
+        };
+
+        Some((params[0].clone(), params[1].clone()))
+    }
+
+    /// Gets the ID of the message this line is a reply to, from the
+    /// `+reply` tag or its draft spelling, `+draft/reply`.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The referenced message ID, or [`None`] if
+    ///   this line is not a reply.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = "@+draft/reply=abc123 PRIVMSG #rickastley :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.reply_to(), Some("abc123"));
+    /// ```
+    ///
+    pub fn reply_to(&self) -> Option<&str> {
+        self.tags
+            .get("+reply")
+            .or_else(|| self.tags.get("+draft/reply"))
+            .map(|x| x.as_str())
+    }
+
+    /// Sets a tag on this line, overwriting any existing value.
+    ///
+    /// # Arguments
+    /// - `key` - The tag's key.
+    /// - `value` - The tag's value.
+    ///
+    /// # Example
+    /// ```
+    /// let mut line = ircparser::Line::from(("PRIVMSG", vec!["#rickastley", "Never gonna give you up!"]));
+    /// line.set_tag("msgid", "abc123");
+    ///
+    /// assert_eq!(&line.tags["msgid"], "abc123");
+    /// ```
+    ///
+    pub fn set_tag(&mut self, key: &str, value: &str) {
+        self.bare_tags.remove(key);
+        self.tags.insert(key.to_string(), value.to_string());
+    }
+
+    /// Removes a tag from this line.
+    ///
+    /// # Arguments
+    /// - `key` - The tag's key.
+    ///
+    /// # Returns
+    /// - [`Option<String>`] - The tag's value, or [`None`] if this line
+    ///   didn't have the tag.
+    ///
+    pub fn remove_tag(&mut self, key: &str) -> Option<String> {
+        self.bare_tags.remove(key);
+        self.tags.remove(key)
+    }
+
+    /// Gets the new user and host from a `CHGHOST` command. The affected
+    /// user is this line's source.
+    ///
+    /// # Returns
+    /// - [`Option<(String, String)>`] - The new user and host, or
+    ///   [`None`] if this line is not a `CHGHOST` command.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":rick!user@old.host CHGHOST astley new.host\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.chghost_info(),
+    ///     Some(("astley".to_string(), "new.host".to_string()))
+    /// );
+    /// ```
+    ///
+    pub fn chghost_info(&self) -> Option<(String, String)> {
+        if !self.command.eq_ignore_ascii_case("CHGHOST") || self.params.len() < 2 {
+            return None;
+        }
+
+        Some((self.params[0].clone(), self.params[1].clone()))
+    }
+
+    /// Checks whether this line references a channel, as per
+    /// [`Line::channels`], comparing names using the given casemapping.
+    ///
+    /// # Arguments
+    /// - `channel` - The channel to look for.
+    /// - `chantypes` - The characters channel names may start with.
+    /// - `casemapping` - The [`CaseMapping`] to compare names with.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line references the channel.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::CaseMapping;
+    ///
+    /// let line = ircparser::parse("JOIN #Rick[astley]\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert!(line.references_channel("#rick{astley}", "#&", CaseMapping::Rfc1459));
+    /// assert!(!line.references_channel("#rick{astley}", "#&", CaseMapping::Ascii));
+    /// ```
+    ///
+    pub fn references_channel(
+        &self,
+        channel: &str,
+        chantypes: &str,
+        casemapping: CaseMapping,
+    ) -> bool {
+        self.channels(chantypes)
+            .iter()
+            .any(|x| casemapping.eq(x, channel))
+    }
+
+    /// Checks whether this line was sent by a bot, as marked by the
+    /// IRCv3 bot mode `bot` tag (or its draft spelling, `draft/bot`).
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line was sent by a bot.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = "@bot :rick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert!(line.is_from_bot());
+    /// ```
+    ///
+    pub fn is_from_bot(&self) -> bool {
+        self.tags.contains_key("bot") || self.tags.contains_key("draft/bot")
+    }
+
+    /// Gets the typing state from this line's `+typing` tag.
+    ///
+    /// # Returns
+    /// - [`Option<TypingState>`] - The typing state, or [`None`] if the
+    ///   tag is not present or its value is not known.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::TypingState;
+    ///
+    /// let line = ircparser::parse("@+typing=active TAGMSG #rickastley\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.typing_state(), Some(TypingState::Active));
+    /// ```
+    ///
+    pub fn typing_state(&self) -> Option<TypingState> {
+        TypingState::from_tag(self.tags.get("+typing")?)
+    }
+
+    /// Gets this line's `label` tag, used by the IRCv3 labeled-response
+    /// capability to match responses to the commands that caused them.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The label, or [`None`] if there isn't one.
+    ///
+    pub fn label(&self) -> Option<&str> {
+        self.tags.get("label").map(|x| x.as_str())
+    }
+
+    /// Creates a copy of this line with its `label` tag set, for sending
+    /// a command with the IRCv3 labeled-response capability.
+    ///
+    /// # Arguments
+    /// - `label` - The label to set.
+    ///
+    /// # Returns
+    /// - [`Line`] - The new [`Line`] instance.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::Line::from(("WHOIS", vec!["rick"])).with_label("abc123");
+    ///
+    /// assert_eq!(line.label(), Some("abc123"));
+    /// assert_eq!(line.to_string(), "@label=abc123 WHOIS rick");
+    /// ```
+    ///
+    pub fn with_label(&self, label: &str) -> Self {
+        let mut line = self.clone();
+        line.set_tag("label", label);
+        line
+    }
+
+    /// Gets the idle time and signon time from an `RPL_WHOISIDLE`
+    /// (`317`) reply.
+    ///
+    /// # Returns
+    /// - [`Option<(u64, u64)>`] - The number of seconds the user has been
+    ///   idle and the unix time they signed on, or [`None`] if this line
+    ///   is not a `317` reply or the times are malformed.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":server 317 me rick 42 1609459200 :seconds idle, signon time\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.whois_idle(), Some((42, 1609459200)));
+    /// ```
+    ///
+    pub fn whois_idle(&self) -> Option<(u64, u64)> {
+        if self.command != "317" || self.params.len() < 4 {
+            return None;
+        }
+
+        let idle = self.params[2].parse().ok()?;
+        let signon = self.params[3].parse().ok()?;
+        Some((idle, signon))
+    }
+
+    /// Parses this line as an IRCv3 MONITOR notification, either
+    /// `RPL_MONONLINE` (`730`) or `RPL_MONOFFLINE` (`731`).
+    ///
+    /// # Returns
+    /// - [`Option<(bool, Vec<Source>)>`] - Whether the listed users came
+    ///   online, and the users themselves, or [`None`] if this line is
+    ///   not a `730` or `731` reply.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":server 730 me :rick!user@host,nick!user@host\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    /// let (online, sources) = line.monitor_notification().unwrap();
+    ///
+    /// assert!(online);
+    /// assert_eq!(sources[0].nick, "rick");
+    /// assert_eq!(sources[1].nick, "nick");
+    /// ```
+    ///
+    pub fn monitor_notification(&self) -> Option<(bool, Vec<Source>)> {
+        let online = match self.command.as_str() {
+            "730" => true,
+            "731" => false,
+            _ => return None,
+        };
+
+        let targets = self.params.get(1)?;
+        let sources = targets
+            .split(',')
+            .filter(|x| !x.is_empty())
+            .map(Source::parse)
+            .collect();
+
+        Some((online, sources))
+    }
+
+    /// Gets the new realname from a `SETNAME` command. The affected user
+    /// is this line's source.
+    ///
+    /// # Returns
+    /// - [`Option<String>`] - The new realname, or [`None`] if this line
+    ///   is not a `SETNAME` command.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":rick!user@host SETNAME :Rick Astley\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.setname(), Some("Rick Astley".to_string()));
+    /// ```
+    ///
+    pub fn setname(&self) -> Option<String> {
+        if !self.command.eq_ignore_ascii_case("SETNAME") {
+            return None;
+        }
+
+        self.params.last().cloned()
+    }
+
+    /// Checks whether this line has the same content as another,
+    /// ignoring tags. This is useful for deduplicating messages which
+    /// differ only in tags such as `msgid` or `time`, such as those
+    /// echoed back by the `echo-message` capability.
+    ///
+    /// # Arguments
+    /// - `other` - The line to compare with.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether the lines have equal sources, commands, and
+    ///   params.
+    ///
+    /// # Example
+    /// ```
+    /// let a = ircparser::parse("@msgid=abc :rick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n");
+    /// let b = ircparser::parse(":rick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n");
+    /// let a = a.unwrap().pop_front().unwrap();
+    /// let b = b.unwrap().pop_front().unwrap();
+    ///
+    /// assert!(a.content_eq(&b));
+    /// assert_ne!(a, b);
+    /// ```
+    ///
+    pub fn content_eq(&self, other: &Line) -> bool {
+        self.source == other.source && self.command == other.command && self.params == other.params
+    }
+
+    /// Gets the text of a `WALLOPS` (or `GLOBOPS`) operator broadcast.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The broadcast text, or [`None`] if this line
+    ///   is not a `WALLOPS` or `GLOBOPS` command.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":irc.example.com WALLOPS :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.wallops_text(), Some("Never gonna give you up!"));
+    /// ```
+    ///
+    pub fn wallops_text(&self) -> Option<&str> {
+        if !self.command.eq_ignore_ascii_case("WALLOPS")
+            && !self.command.eq_ignore_ascii_case("GLOBOPS")
+        {
+            return None;
+        }
+
+        self.params.last().map(|x| x.as_str())
+    }
+
+    /// Checks whether this line was sent by the given nick, comparing
+    /// nicks using the given casemapping. With the `echo-message`
+    /// capability, this can be used with the client's own nick to
+    /// detect echoed messages.
+    ///
+    /// # Arguments
+    /// - `nick` - The nick to check for.
+    /// - `casemapping` - The [`CaseMapping`] to compare nicks with.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line's source has the given nick. This
+    ///   will be `false` if there is no source.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::CaseMapping;
+    ///
+    /// let msg = ":Rick[]!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert!(line.is_from("rick{}", CaseMapping::Rfc1459));
+    /// ```
+    ///
+    pub fn is_from(&self, nick: &str, casemapping: CaseMapping) -> bool {
+        self.source_nick().is_some_and(|x| casemapping.eq(x, nick))
+    }
+
+    /// Gets the reaction carried by this line's `+draft/react` tag,
+    /// alongside the ID of the message being reacted to (as per
+    /// [`Line::reply_to`]).
+    ///
+    /// # Returns
+    /// - [`Option<(&str, &str)>`] - The referenced message ID and the
+    ///   reaction, or [`None`] if either tag is missing.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = "@+draft/reply=abc123;+draft/react=🎵 TAGMSG #rickastley\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.reaction(), Some(("abc123", "🎵")));
+    /// ```
+    ///
+    pub fn reaction(&self) -> Option<(&str, &str)> {
+        let msgid = self.reply_to()?;
+        let react = self.tags.get("+draft/react")?;
+        Some((msgid, react.as_str()))
+    }
+
+    /// Formats this line as a raw IRC message, as per its
+    /// [`Display`](std::fmt::Display) implementation, checking first
+    /// that it can be represented as one.
+    ///
+    /// # Returns
+    /// - [`String`] - The formatted message, without the trailing
+    ///   `\r\n`.
+    ///
+    /// # Errors
+    /// A [`ParseError`] is returned if:
+    /// - the command is empty, contains a space, CR, LF, or NUL
+    ///   character, or starts with a `:` or `@`;
+    /// - any tag key is invalid (see
+    ///   [`ParseConfig::strict_tag_keys`](crate::ParseConfig::strict_tag_keys)),
+    ///   or any tag value contains a NUL character (CR and LF are
+    ///   escaped, so are allowed);
+    /// - the source is empty, or contains a space, CR, LF, or NUL
+    ///   character;
+    /// - any param contains a CR, LF, or NUL character; or
+    /// - any param other than the last is empty, contains a space, or
+    ///   starts with a colon.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::Line::from(("PRIVMSG", vec!["#rickastley", "Never gonna give you up!"]));
+    /// assert_eq!(line.try_to_string().unwrap(), "PRIVMSG #rickastley :Never gonna give you up!");
+    ///
+    /// let line = ircparser::Line::default();
+    /// assert!(line.try_to_string().is_err());
+    /// ```
+    ///
+    pub fn try_to_string(&self) -> Result<String, ParseError> {
+        const NEWLINE_OR_NUL: [char; 3] = ['\r', '\n', '\0'];
+
+        if self.command.is_empty()
+            || self.command.contains(' ')
+            || self.command.contains(NEWLINE_OR_NUL)
+            || self.command.starts_with([':', '@'])
+        {
+            return Err(ParseError::new(&format!(
+                "invalid command {:?}",
+                self.command
+            )));
+        }
+
+        for (key, value) in &self.tags {
+            if !is_valid_key(key) || value.contains('\0') {
+                return Err(ParseError::new(&format!("invalid tag {key:?}")));
+            }
+        }
+
+        if let Some(source) = &self.source {
+            let name = source.strip_prefix(':').unwrap_or(source);
+            if name.is_empty() || name.contains(' ') || name.contains(NEWLINE_OR_NUL) {
+                return Err(ParseError::new(&format!("invalid source {source:?}")));
+            }
+        }
+
+        for (i, param) in self.params.iter().enumerate() {
+            let is_last = i + 1 == self.params.len();
+
+            if param.contains(NEWLINE_OR_NUL) || (!is_last && !is_middle_param(param)) {
+                return Err(ParseError::new(&format!("invalid param {param:?}")));
+            }
+        }
+
+        Ok(self.to_string())
+    }
+
+    /// Gets each channel from a `JOIN` command, paired with its key.
+    /// Keys are matched to channels by position.
+    ///
+    /// # Returns
+    /// - [`Vec<(String, Option<String>)>`] - Each channel and its key, or
+    ///   [`None`] if it has no key. This will be empty if this line is
+    ///   not a `JOIN` command, or is `JOIN 0` (which parts all
+    ///   channels).
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("JOIN #rick,#astley never\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.join_targets(),
+    ///     vec![
+    ///         ("#rick".to_string(), Some("never".to_string())),
+    ///         ("#astley".to_string(), None),
+    ///     ]
+    /// );
+    /// ```
+    ///
+    pub fn join_targets(&self) -> Vec<(String, Option<String>)> {
+        if !self.command.eq_ignore_ascii_case("JOIN") {
+            return Vec::new();
+        }
+
+        let channels = match self.params.first() {
+            Some(x) if x != "0" => x,
+            _ => return Vec::new(),
+        };
+        let mut keys = self.params.get(1).map(|x| x.split(','));
+
+        channels
+            .split(',')
+            .map(|channel| {
+                let key = keys
+                    .as_mut()
+                    .and_then(|x| x.next())
+                    .filter(|x| !x.is_empty())
+                    .map(|x| x.to_string());
+                (channel.to_string(), key)
+            })
+            .collect()
+    }
+
+    /// Checks whether this line is a `PING` command.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is a `PING` command.
+    ///
+    pub fn is_ping(&self) -> bool {
+        self.command.eq_ignore_ascii_case("PING")
+    }
+
+    /// Creates the `PONG` reply to this `PING` command.
+    ///
+    /// For the client form (`PING :token`), the token is echoed back
+    /// (`PONG token`). For the server-to-server form
+    /// (`PING origin destination`), the params are swapped, so the
+    /// destination replies with `PONG destination origin`. The reply's
+    /// last param is not written as a trailing param unless it has to
+    /// be, such as when it contains a space.
+    ///
+    /// # Returns
+    /// - [`Option<Line>`] - The `PONG` reply, or [`None`] if this line is
+    ///   not a `PING` command.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("PING :irc.example.com\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.make_pong().unwrap().to_string(), "PONG irc.example.com");
+    ///
+    /// let line = ircparser::parse("PING server1.net :server2.net\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.make_pong().unwrap().to_string(), "PONG server2.net server1.net");
+    /// ```
+    ///
+    pub fn make_pong(&self) -> Option<Line> {
+        if !self.is_ping() {
+            return None;
+        }
+
+        let params = match self.params.as_slice() {
+            [origin, destination, ..] => vec![destination.clone(), origin.clone()],
+            params => params.to_vec(),
+        };
+
+        Some(Line::new(HashMap::new(), None, "PONG", params))
+    }
+
+    /// Formats this line's tags as they appear in a raw message, without
+    /// the leading `@`.
+    fn tags_string(&self, format: &RawFormat) -> String {
+        let mut tags: Vec<(&String, &String)> = self.tags.iter().collect();
+        tags.sort();
+
+        let tags: Vec<String> = tags
+            .iter()
+            .map(|(k, v)| {
+                if v.is_empty() && format.bare_tags && self.bare_tags.contains(*k) {
+                    k.to_string()
+                } else if format.escape_tags {
+                    format!("{k}={}", escape_value(v))
+                } else {
+                    format!("{k}={v}")
+                }
+            })
+            .collect();
+        tags.join(";")
+    }
+
+    /// Gets this line's components as strings, for use in custom
+    /// formatters. The components are, in order, the tags (formatted as
+    /// they would be in a raw message, without the leading `@`), the
+    /// source (without the leading `:`), the command, and each param.
+    ///
+    /// # Returns
+    /// - [`Vec<String>`] - This line's components. The tags and source
+    ///   will be empty strings if not present.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("PRIVMSG #rickastley :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.components(),
+    ///     vec!["", "", "PRIVMSG", "#rickastley", "Never gonna give you up!"]
+    /// );
+    /// ```
+    ///
+    pub fn components(&self) -> Vec<String> {
+        let source = match &self.source {
+            Some(x) => x.strip_prefix(':').unwrap_or(x).to_string(),
+            None => String::new(),
+        };
+
+        let mut components = vec![
+            self.tags_string(&RawFormat::DISPLAY),
+            source,
+            self.command.clone(),
+        ];
+        components.extend(self.params.iter().cloned());
+        components
+    }
+
+    /// Gets the time this line was sent, as given by the server. This
+    /// checks the IRCv3 `time` tag (an ISO 8601 UTC timestamp), then the
+    /// `t` tag (unix seconds) used by some networks. The `t` tag is also
+    /// used if the `time` tag is malformed.
+    ///
+    /// # Returns
+    /// - [`Option<SystemTime>`] - The time this line was sent, or
+    ///   [`None`] if neither tag is present or valid.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let msg = "@time=2021-01-01T00:00:00.000Z PRIVMSG #rickastley :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.server_time(), Some(UNIX_EPOCH + Duration::from_secs(1609459200)));
+    /// ```
+    ///
+    pub fn server_time(&self) -> Option<SystemTime> {
+        if let Some(time) = self.tags.get("time").and_then(|x| parse_iso8601(x)) {
+            return Some(time);
+        }
+
+        let secs = self.tags.get("t")?.parse().ok()?;
+        Some(UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// Checks whether this line is a `PRIVMSG` sent directly to the
+    /// given nick, rather than to a channel.
+    ///
+    /// # Arguments
+    /// - `my_nick` - The nick to check for, usually the client's own.
+    /// - `chantypes` - The characters channel names may start with.
+    /// - `casemapping` - The [`CaseMapping`] to compare nicks with.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is a direct message to `my_nick`.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::CaseMapping;
+    ///
+    /// let line = ircparser::parse(":nick!user@host PRIVMSG Rick :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert!(line.is_direct_to("rick", "#&", CaseMapping::Rfc1459));
+    /// ```
+    ///
+    pub fn is_direct_to(&self, my_nick: &str, chantypes: &str, casemapping: CaseMapping) -> bool {
+        if !self.command.eq_ignore_ascii_case("PRIVMSG") {
+            return false;
+        }
+
+        match self.params.first() {
+            Some(target) => {
+                !target.starts_with(|c| chantypes.contains(c)) && casemapping.eq(target, my_nick)
+            }
+            None => false,
+        }
+    }
+
+    /// Writes this line as a raw IRC message in the given format.
+    fn write_raw(&self, f: &mut impl std::fmt::Write, format: &RawFormat) -> std::fmt::Result {
+        if !self.tags.is_empty() {
+            write!(f, "@{} ", self.tags_string(format))?;
+        }
+
+        if let Some(source) = &self.source {
+            if source.starts_with(':') {
+                write!(f, "{source} ")?;
+            } else {
+                write!(f, ":{source} ")?;
+            }
+        }
+
+        write!(f, "{}", self.command)?;
+
+        if let Some((last, middle)) = self.params.split_last() {
+            for param in middle {
+                write!(f, " {param}")?;
+            }
+
+            if format.always_trailing
+                || self.has_trailing
+                || last.is_empty()
+                || last.contains(' ')
+                || last.starts_with(':')
+            {
+                write!(f, " :{last}")?;
+            } else {
+                write!(f, " {last}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Formats this line as a raw IRC message in the form Twitch sends.
+    /// This matches the [`Display`](std::fmt::Display) implementation,
+    /// except that the message body of a `PRIVMSG`, `NOTICE`,
+    /// `USERNOTICE`, `WHISPER`, `CLEARCHAT`, or `CLEARMSG` (as well as
+    /// the token of a `PING` or `PONG`) is always written as a trailing
+    /// param, even when it is a single word, and tags with empty values
+    /// always keep their `=` (even ones in [`Line::bare_tags`]), as
+    /// Twitch does.
+    ///
+    /// # Returns
+    /// - [`String`] - The formatted message, without the trailing
+    ///   `\r\n`.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = "@color=#FF0000;display-name=Rick;emotes :rick!rick@rick.tmi.twitch.tv PRIVMSG #rickastley hello\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.to_string(), msg.trim_end());
+    /// assert_eq!(
+    ///     line.to_twitch_string(),
+    ///     "@color=#FF0000;display-name=Rick;emotes= :rick!rick@rick.tmi.twitch.tv PRIVMSG #rickastley :hello"
+    /// );
+    /// ```
+    ///
+    pub fn to_twitch_string(&self) -> String {
+        let always_trailing = match self.command.to_ascii_uppercase().as_str() {
+            "PRIVMSG" | "NOTICE" | "USERNOTICE" | "WHISPER" | "CLEARCHAT" | "CLEARMSG" => {
+                self.params.len() > 1
+            }
+            "PING" | "PONG" => true,
+            _ => false,
+        };
+
+        let mut text = String::new();
+        let format = RawFormat {
+            always_trailing,
+            escape_tags: true,
+            bare_tags: false,
+        };
+        self.write_raw(&mut text, &format)
+            .expect("writing to a String cannot fail");
+        text
+    }
+
+    /// Checks whether this line is a `QUIT` caused by a netsplit. Such
+    /// reasons are made up of the names of the two servers that split,
+    /// separated by a space (such as `irc.example.com hub.example.com`).
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is a netsplit `QUIT`.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":rick!rick@rick.com QUIT :irc.example.com hub.example.com\r\n").unwrap().pop_front().unwrap();
+    /// assert!(line.is_netsplit_quit());
+    ///
+    /// let line = ircparser::parse(":rick!rick@rick.com QUIT :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    /// assert!(!line.is_netsplit_quit());
+    /// ```
+    ///
+    pub fn is_netsplit_quit(&self) -> bool {
+        if !self.command.eq_ignore_ascii_case("QUIT") {
+            return false;
+        }
+
+        let is_server_name = |name: &str| {
+            name.contains('.')
+                && !name.starts_with('.')
+                && !name.ends_with('.')
+                && !name.contains("..")
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        };
+
+        match self
+            .params
+            .first()
+            .map(|x| x.split(' ').collect::<Vec<_>>())
+        {
+            Some(servers) => {
+                servers.len() == 2
+                    && servers[0] != servers[1]
+                    && servers.iter().all(|x| is_server_name(x))
+            }
+            None => false,
+        }
+    }
+
+    /// Gets the old and new nicks from a `NICK` command. The old nick is
+    /// taken from this line's source.
+    ///
+    /// # Returns
+    /// - [`Option<(String, String)>`] - The old nick and the new nick, or
+    ///   [`None`] if this line is not a `NICK` command or has no source.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":rick!user@host NICK astley\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.nick_change(),
+    ///     Some(("rick".to_string(), "astley".to_string()))
+    /// );
+    /// ```
+    ///
+    pub fn nick_change(&self) -> Option<(String, String)> {
+        if !self.command.eq_ignore_ascii_case("NICK") {
+            return None;
+        }
+
+        Some((
+            self.source_nick()?.to_string(),
+            self.params.first()?.clone(),
+        ))
+    }
+
+    /// Gets the channel and its modes from an `RPL_CHANNELMODEIS` (`324`)
+    /// reply.
+    ///
+    /// # Returns
+    /// - [`Option<(String, String)>`] - The channel and its mode string,
+    ///   including any mode arguments, or [`None`] if this line is not a
+    ///   `324` reply.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":irc.example.com 324 rick #rickastley +lnt 10\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.channel_mode_is(),
+    ///     Some(("#rickastley".to_string(), "+lnt 10".to_string()))
+    /// );
+    /// ```
+    ///
+    pub fn channel_mode_is(&self) -> Option<(String, String)> {
+        if self.command != "324" || self.params.len() < 3 {
+            return None;
+        }
+
+        Some((self.params[1].clone(), self.params[2..].join(" ")))
+    }
+
+    /// Gets the channel and the time it was created from an
+    /// `RPL_CREATIONTIME` (`329`) reply.
+    ///
+    /// # Returns
+    /// - [`Option<(String, SystemTime)>`] - The channel and its creation
+    ///   time, or [`None`] if this line is not a `329` reply or the
+    ///   timestamp is malformed.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let line = ircparser::parse(":irc.example.com 329 rick #rickastley 1656000000\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.channel_creation_time(),
+    ///     Some(("#rickastley".to_string(), UNIX_EPOCH + Duration::from_secs(1656000000)))
+    /// );
+    /// ```
+    ///
+    pub fn channel_creation_time(&self) -> Option<(String, SystemTime)> {
+        if self.command != "329" || self.params.len() < 3 {
+            return None;
+        }
+
+        let secs = self.params[2].parse().ok()?;
+        Some((
+            self.params[1].clone(),
+            UNIX_EPOCH + Duration::from_secs(secs),
+        ))
+    }
+
+    /// Gets the channel a private message was sent in the context of,
+    /// from the `+draft/channel-context` tag.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The referenced channel, or [`None`] if this
+    ///   line has no channel context.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = "@+draft/channel-context=#rickastley PRIVMSG rick :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.channel_context(), Some("#rickastley"));
+    /// ```
+    ///
+    pub fn channel_context(&self) -> Option<&str> {
+        self.tags
+            .get("+draft/channel-context")
+            .map(|x| x.as_str())
+            .filter(|x| !x.is_empty())
+    }
+
+    /// Gets the text of a CTCP `ACTION` (sent by `/me`). The final
+    /// `\x01` delimiter is optional, as some clients leave it out.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The action's text, or [`None`] if this line
+    ///   is not a `PRIVMSG` containing an action.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("PRIVMSG #rickastley :\x01ACTION dances\x01\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.action_text(), Some("dances"));
+    /// ```
+    ///
+    pub fn action_text(&self) -> Option<&str> {
+        if !self.command.eq_ignore_ascii_case("PRIVMSG") || self.params.len() < 2 {
+            return None;
+        }
+
+        let text = self.params[1].strip_prefix("\x01ACTION")?;
+        let text = text.strip_suffix('\x01').unwrap_or(text);
+
+        if text.is_empty() {
+            Some(text)
+        } else {
+            text.strip_prefix(' ')
+        }
+    }
+
+    /// Checks whether this line is a CTCP `ACTION`, as per
+    /// [`Line::action_text`].
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is an action.
+    ///
+    pub fn is_action(&self) -> bool {
+        self.action_text().is_some()
+    }
+
+    /// Checks whether this line is a `TAGMSG` command.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is a `TAGMSG` command.
+    ///
+    pub fn is_tagmsg(&self) -> bool {
+        self.command.eq_ignore_ascii_case("TAGMSG")
+    }
+
+    /// Creates a `TAGMSG` command with no tags. As a `TAGMSG` carries
+    /// no text, its only param is the target.
+    ///
+    /// # Arguments
+    /// - `target` - The channel or nick to send the `TAGMSG` to.
+    ///
+    /// # Returns
+    /// - [`Line`] - The `TAGMSG` command.
+    ///
+    /// # Example
+    /// ```
+    /// let mut line = ircparser::Line::tagmsg("#rickastley");
+    /// line.set_tag("+typing", "active");
+    ///
+    /// assert_eq!(line.to_string(), "@+typing=active TAGMSG #rickastley");
+    /// ```
+    ///
+    pub fn tagmsg(target: &str) -> Self {
+        Self::new(HashMap::new(), None, "TAGMSG", vec![target.to_string()])
+    }
+
+    /// Formats this line as a raw IRC message, as per its
+    /// [`Display`](std::fmt::Display) implementation, but without
+    /// escaping tag values. This is intended for lines parsed with
+    /// [`ParseConfig::raw_tag_values`](crate::ParseConfig::raw_tag_values)
+    /// set, whose tag values are still escaped.
+    ///
+    /// Tags with empty values are written as they were sent, either bare
+    /// (`key`) or with an `=` (`key=`). Tags are written in key order, so
+    /// the tag section is only byte-for-byte identical to the original
+    /// if it was sent in key order too.
+    ///
+    /// # Returns
+    /// - [`String`] - The formatted message, without the trailing
+    ///   `\r\n`.
+    ///
+    /// # Example
+    /// ```
+    /// let config = ircparser::ParseConfig {
+    ///     raw_tag_values: true,
+    ///     ..Default::default()
+    /// };
+    /// let msg = r"@id=a\xb PRIVMSG #rickastley :Never gonna give you up!";
+    /// let line = ircparser::parse_with(msg, &config).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.to_string_raw_tags(), msg);
+    /// ```
+    ///
+    pub fn to_string_raw_tags(&self) -> String {
+        let mut text = String::new();
+        let format = RawFormat {
+            escape_tags: false,
+            ..RawFormat::DISPLAY
+        };
+        self.write_raw(&mut text, &format)
+            .expect("writing to a String cannot fail");
+        text
+    }
+
+    /// Checks that this line has at least the given number of params.
+    /// This is useful as a guard before indexing into
+    /// [`params`](Line::params).
+    ///
+    /// # Arguments
+    /// - `n` - The minimum number of params.
+    ///
+    /// # Errors
+    /// A [`ParseError`] is returned if this line has fewer than `n`
+    /// params.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("PRIVMSG #rickastley :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert!(line.require_params(2).is_ok());
+    /// assert!(line.require_params(3).is_err());
+    /// ```
+    ///
+    pub fn require_params(&self, n: usize) -> Result<(), ParseError> {
+        if self.params.len() < n {
+            return Err(ParseError::new(&format!(
+                "{} requires at least {n} params, but has {}",
+                self.command,
+                self.params.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Gets the nick the server assigned to the client from an
+    /// `RPL_WELCOME` (`001`) reply. This can differ from the nick that
+    /// was requested.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The client's nick, or [`None`] if this line is
+    ///   not a `001` reply.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":irc.example.com 001 rick :Welcome to the Example IRC Network, rick\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.welcome_nick(), Some("rick"));
+    /// ```
+    ///
+    pub fn welcome_nick(&self) -> Option<&str> {
+        if self.command != "001" {
+            return None;
+        }
+
+        self.params.first().map(|x| x.as_str())
+    }
+
+    /// Gets a channel's details from an `RPL_LIST` (`322`) reply.
+    ///
+    /// # Returns
+    /// - [`Option<(String, u32, String)>`] - The channel, the number of
+    ///   visible users in it, and its topic (which will be empty if it
+    ///   has none), or [`None`] if this line is not a `322` reply or the
+    ///   user count is malformed.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":irc.example.com 322 rick #rickastley 42 :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.list_entry(),
+    ///     Some(("#rickastley".to_string(), 42, "Never gonna give you up!".to_string()))
+    /// );
+    /// ```
+    ///
+    pub fn list_entry(&self) -> Option<(String, u32, String)> {
+        if self.command != "322" || self.params.len() < 3 {
+            return None;
+        }
+
+        let users = self.params[2].parse().ok()?;
+        let topic = self.params.get(3).cloned().unwrap_or_default();
+        Some((self.params[1].clone(), users, topic))
+    }
+
+    /// Checks whether this line is a `CAP` command, as used in
+    /// capability negotiation.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is a `CAP` command.
+    ///
+    pub fn is_cap(&self) -> bool {
+        self.command.eq_ignore_ascii_case("CAP")
+    }
+
+    /// Checks whether this line is an `AUTHENTICATE` command, as used in
+    /// SASL authentication.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is an `AUTHENTICATE` command.
+    ///
+    pub fn is_authenticate(&self) -> bool {
+        self.command.eq_ignore_ascii_case("AUTHENTICATE")
+    }
+
+    /// Gets the reason from an `ERROR` command, which servers send
+    /// before closing a connection.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The error message, or [`None`] if this line is
+    ///   not an `ERROR` command.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("ERROR :Closing link: rick (Quit: bye)\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.error_message(), Some("Closing link: rick (Quit: bye)"));
+    /// ```
+    ///
+    pub fn error_message(&self) -> Option<&str> {
+        if !self.command.eq_ignore_ascii_case("ERROR") {
+            return None;
+        }
+
+        self.params.last().map(|x| x.as_str())
+    }
+
+    /// Gets the status prefix from the target of a `PRIVMSG`, `NOTICE`,
+    /// or `TAGMSG` sent with `STATUSMSG` (such as to `@#channel`, which
+    /// only reaches channel operators).
+    ///
+    /// # Arguments
+    /// - `prefixes` - The prefix characters that may be used (the
+    ///   `STATUSMSG` ISUPPORT token, such as `"@+"`).
+    ///
+    /// # Returns
+    /// - [`Option<(char, &str)>`] - The prefix and the bare channel, or
+    ///   [`None`] if the target has no prefix.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("PRIVMSG @#rickastley :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.statusmsg_prefix("@+"), Some(('@', "#rickastley")));
+    /// ```
+    ///
+    pub fn statusmsg_prefix(&self, prefixes: &str) -> Option<(char, &str)> {
+        if !["PRIVMSG", "NOTICE", "TAGMSG"]
+            .iter()
+            .any(|x| self.command.eq_ignore_ascii_case(x))
+        {
+            return None;
+        }
+
+        let target = self.params.first()?;
+        let prefix = target.chars().next().filter(|&c| prefixes.contains(c))?;
+        let channel = &target[prefix.len_utf8()..];
+
+        (!channel.is_empty()).then_some((prefix, channel))
+    }
+
+    /// Parses this line as an `account-notify` `ACCOUNT` command, which is
+    /// sent when the source logs in to or out of an account.
+    ///
+    /// # Returns
+    /// - [`Option<Option<String>>`] - The account the source is now logged
+    ///   in to, or [`Some(None)`](Some) if they logged out, or [`None`]
+    ///   if this line is not an `ACCOUNT` command.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":rick!user@host ACCOUNT astley\r\n").unwrap().pop_front().unwrap();
+    /// assert_eq!(line.account_notify(), Some(Some("astley".to_string())));
+    ///
+    /// let line = ircparser::parse(":rick!user@host ACCOUNT *\r\n").unwrap().pop_front().unwrap();
+    /// assert_eq!(line.account_notify(), Some(None));
+    /// ```
+    ///
+    pub fn account_notify(&self) -> Option<Option<String>> {
+        if !self.command.eq_ignore_ascii_case("ACCOUNT") {
+            return None;
+        }
+
+        let account = self.params.first()?;
+        Some((account != "*").then(|| account.clone()))
+    }
+
+    /// Creates a copy of this line with its source set, overwriting any
+    /// existing source. As with parsed lines, the source is stored with
+    /// a leading `:`, which is added if absent.
+    ///
+    /// # Arguments
+    /// - `source` - The source to set.
+    ///
+    /// # Returns
+    /// - [`Line`] - The new [`Line`] instance.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::Line::from(("PRIVMSG", vec!["#rickastley", "Never gonna give you up!"]))
+    ///     .with_source("rick!user@host");
+    ///
+    /// assert_eq!(line.source.as_deref(), Some(":rick!user@host"));
+    /// assert_eq!(line.to_string(), ":rick!user@host PRIVMSG #rickastley :Never gonna give you up!");
+    /// ```
+    ///
+    pub fn with_source(&self, source: &str) -> Self {
+        let mut line = self.clone();
+        line.source = Some(if source.starts_with(':') {
+            source.to_string()
+        } else {
+            format!(":{source}")
+        });
+        line
+    }
+
+    /// Gets the token from a `PONG` reply, which echoes the token sent in
+    /// the corresponding `PING`. This is useful for measuring lag.
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The token, or [`None`] if this line is not a
+    ///   `PONG` command.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":irc.example.com PONG irc.example.com :LAG123\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.pong_token(), Some("LAG123"));
+    /// ```
+    ///
+    pub fn pong_token(&self) -> Option<&str> {
+        if !self.command.eq_ignore_ascii_case("PONG") {
+            return None;
+        }
+
+        self.params.last().map(|x| x.as_str())
+    }
+
+    /// Gets a stable identity for this line, for deduplicating messages
+    /// (such as those replayed after reconnecting). This is the line's
+    /// `msgid` tag if it has one. Otherwise, it is a hash of the
+    /// source, command, params, and `time` tag, written as 16 hex
+    /// digits. The hash (64-bit FNV-1a) does not change between runs or
+    /// versions of this crate.
+    ///
+    /// # Returns
+    /// - [`String`] - This line's identity.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("@msgid=abc123 PRIVMSG #rickastley :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    /// assert_eq!(line.identity(), "abc123");
+    ///
+    /// let line = ircparser::parse("PRIVMSG #rickastley :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    /// assert_eq!(line.identity().len(), 16);
+    /// ```
+    ///
+    pub fn identity(&self) -> String {
+        if let Some(msgid) = self.tags.get("msgid").filter(|x| !x.is_empty()) {
+            return msgid.clone();
+        }
+
+        let source = self.source.as_deref().unwrap_or("");
+        let source = source.strip_prefix(':').unwrap_or(source);
+        let time = self.tags.get("time").map_or("", |x| x.as_str());
+        let fields = [source, &self.command, time]
+            .into_iter()
+            .chain(self.params.iter().map(|x| x.as_str()));
+
+        // Fields are separated by NUL, which can't appear in any of them.
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for field in fields {
+            for byte in field.bytes().chain([0]) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+
+        format!("{hash:016x}")
+    }
+
+    /// Replaces the nick in this line's source with a new one, keeping
+    /// the user and host. This does nothing if this line has no source.
+    ///
+    /// # Arguments
+    /// - `new_nick` - The new nick.
+    ///
+    /// # Example
+    /// ```
+    /// let mut line = ircparser::parse(":rick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\n")
+    ///     .unwrap()
+    ///     .pop_front()
+    ///     .unwrap();
+    /// line.rewrite_source_nick("astley");
+    ///
+    /// assert_eq!(line.source.as_deref(), Some(":astley!user@host"));
+    /// ```
+    ///
+    pub fn rewrite_source_nick(&mut self, new_nick: &str) {
+        let Some(source) = &self.source else {
+            return;
+        };
+
+        let source = Source::parse(source);
+        let mut rewritten = format!(":{new_nick}");
+        if let Some(user) = source.user {
+            rewritten.push('!');
+            rewritten.push_str(&user);
+        }
+        if let Some(host) = source.host {
+            rewritten.push('@');
+            rewritten.push_str(&host);
+        }
+
+        self.source = Some(rewritten);
+    }
+
+    /// Checks whether this line was sent by a server rather than a user.
+    /// Server sources are a server name (such as `irc.example.com`) or a
+    /// TS6 server ID, with no user or host. A bare nick with no `.` is
+    /// treated as a user.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line's source is a server. This will be
+    ///   [`false`] if this line has no source.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":irc.example.com NOTICE * :*** Looking up your hostname...\r\n").unwrap().pop_front().unwrap();
+    /// assert!(line.is_from_server());
+    ///
+    /// let line = ircparser::parse(":rick!user@host NOTICE astley :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+    /// assert!(!line.is_from_server());
+    /// ```
+    ///
+    pub fn is_from_server(&self) -> bool {
+        match &self.source {
+            Some(source) => {
+                let source = Source::parse(source);
+                source.user.is_none()
+                    && source.host.is_none()
+                    && (source.nick.contains('.') || source.is_sid())
+            }
+            None => false,
+        }
+    }
+
+    /// Gets the channels a user is in from an `RPL_WHOISCHANNELS`
+    /// (`319`) reply, along with their membership prefixes in each.
+    /// Prefixes are read up to the first channel type character, so
+    /// channels like `&local` keep their sigil even when `&` is also a
+    /// prefix.
+    ///
+    /// # Arguments
+    /// - `prefixes` - The membership prefix symbols the server uses (from
+    ///   the `PREFIX` ISUPPORT token, such as `"@+"`).
+    /// - `chantypes` - The characters channel names may start with (the
+    ///   `CHANTYPES` ISUPPORT token, usually `"#&"`).
+    ///
+    /// # Returns
+    /// - [`Vec<(String, Vec<char>)>`] - Each channel, paired with the
+    ///   user's prefixes in it. This will be empty if this line is not
+    ///   a `319` reply.
+    ///
+    /// # Example
+    /// ```
+    /// let msg = ":irc.example.com 319 me rick :@#rickastley +#never #gonna\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(
+    ///     line.whois_channels("@+", "#&"),
+    ///     vec![
+    ///         ("#rickastley".to_string(), vec!['@']),
+    ///         ("#never".to_string(), vec!['+']),
+    ///         ("#gonna".to_string(), vec![]),
+    ///     ]
+    /// );
+    /// ```
+    ///
+    pub fn whois_channels(&self, prefixes: &str, chantypes: &str) -> Vec<(String, Vec<char>)> {
+        if self.command != "319" || self.params.len() < 3 {
+            return Vec::new();
+        }
+
+        self.params[self.params.len() - 1]
+            .split(' ')
+            .filter(|x| !x.is_empty())
+            .map(|x| {
+                let idx = x
+                    .find(|c| !prefixes.contains(c) || chantypes.contains(c))
+                    .unwrap_or(x.len());
+                (x[idx..].to_string(), x[..idx].chars().collect())
+            })
+            .collect()
+    }
+
+    /// Gets this line's trailing param. Unlike the last element of
+    /// [`params`](Line::params), this is only present if the param was
+    /// a trailing param (sent with a leading `:`).
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The trailing param, or [`None`] if this line
+    ///   doesn't have one.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse("PRIVMSG #rickastley :Never\r\n").unwrap().pop_front().unwrap();
+    /// assert_eq!(line.trailing(), Some("Never"));
+    ///
+    /// let line = ircparser::parse("PRIVMSG #rickastley Never\r\n").unwrap().pop_front().unwrap();
+    /// assert_eq!(line.trailing(), None);
+    /// ```
+    ///
+    pub fn trailing(&self) -> Option<&str> {
+        if !self.has_trailing {
+            return None;
+        }
+
+        self.params.last().map(|x| x.as_str())
+    }
+
+    /// Checks whether this line is a server notice sent to a client that
+    /// has not yet registered (`NOTICE *` or `NOTICE AUTH`), such as
+    /// those reporting hostname lookups while connecting. The source
+    /// must be a server, as per [`Line::is_from_server`], or absent.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this line is a server notice.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::parse(":irc.example.com NOTICE * :*** Looking up your hostname...\r\n").unwrap().pop_front().unwrap();
+    ///
+    /// assert!(line.is_server_notice());
+    /// assert_eq!(line.server_notice_text(), Some("*** Looking up your hostname..."));
+    /// ```
+    ///
+    pub fn is_server_notice(&self) -> bool {
+        self.command.eq_ignore_ascii_case("NOTICE")
+            && self.params.len() == 2
+            && (self.params[0] == "*" || self.params[0].eq_ignore_ascii_case("AUTH"))
+            && (self.source.is_none() || self.is_from_server())
+    }
+
+    /// Gets the text of a server notice, as per
+    /// [`Line::is_server_notice`].
+    ///
+    /// # Returns
+    /// - [`Option<&str>`] - The notice's text, or [`None`] if this line is
+    ///   not a server notice.
+    ///
+    pub fn server_notice_text(&self) -> Option<&str> {
+        if !self.is_server_notice() {
+            return None;
+        }
+
+        Some(&self.params[1])
+    }
+
+    /// Appends a param to this line. As only the last param can be a
+    /// trailing param, this clears [`has_trailing`](Line::has_trailing).
+    /// Use [`Line::push_trailing`] for params which contain spaces.
+    ///
+    /// # Arguments
+    /// - `param` - The param to append.
+    ///
+    /// # Errors
+    /// A [`ParseError`] is returned, and the line left unchanged, if
+    /// `param` is empty, contains a space, starts with a `:`, or contains
+    /// a CR, LF, or NUL character. The same applies to the current last
+    /// param, as it will no longer be the last one. Any of these would
+    /// cause the line to parse back differently once formatted.
+    ///
+    /// # Example
+    /// ```
+    /// let mut line = ircparser::Line::from(("KICK", vec![]));
+    /// line.push_param("#rickastley").unwrap();
+    /// line.push_param("rick").unwrap();
+    ///
+    /// assert_eq!(line.to_string(), "KICK #rickastley rick");
+    /// assert!(line.push_param("Never gonna give you up!").is_err());
+    /// ```
+    ///
+    pub fn push_param(&mut self, param: &str) -> Result<(), ParseError> {
+        self.check_push(param, true)?;
+        self.params.push(param.to_string());
+        self.has_trailing = false;
+        Ok(())
+    }
+
+    /// Appends a trailing param to this line, setting
+    /// [`has_trailing`](Line::has_trailing) so it is always formatted
+    /// with a leading `:`.
+    ///
+    /// # Arguments
+    /// - `text` - The param to append.
+    ///
+    /// # Errors
+    /// A [`ParseError`] is returned, and the line left unchanged, if
+    /// `text` contains a CR, LF, or NUL character, or if the current last
+    /// param could not be written anywhere but last (as per
+    /// [`Line::push_param`]).
+    ///
+    /// # Example
+    /// ```
+    /// let mut line = ircparser::Line::from(("PRIVMSG", vec![]));
+    /// line.push_param("#rickastley").unwrap();
+    /// line.push_trailing("Never").unwrap();
+    ///
+    /// assert_eq!(line.to_string(), "PRIVMSG #rickastley :Never");
+    /// assert!(line.push_trailing("gonna\r\nQUIT").is_err());
+    /// ```
+    ///
+    pub fn push_trailing(&mut self, text: &str) -> Result<(), ParseError> {
+        self.check_push(text, false)?;
+        self.params.push(text.to_string());
+        self.has_trailing = true;
+        Ok(())
+    }
+
+    /// Checks that a param can be pushed onto this line, which turns the
+    /// current last param into a middle one.
+    fn check_push(&self, param: &str, middle: bool) -> Result<(), ParseError> {
+        let invalid = |x: &str| ParseError::new(&format!("invalid param {x:?}"));
+
+        if let Some(last) = self.params.last() {
+            if !is_middle_param(last) || last.contains(['\r', '\n', '\0']) {
+                return Err(invalid(last));
+            }
+        }
+        if (middle && !is_middle_param(param)) || param.contains(['\r', '\n', '\0']) {
+            return Err(invalid(param));
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks whether a param can be written anywhere but last, where it has
+/// no leading `:` to protect it.
+fn is_middle_param(param: &str) -> bool {
+    !(param.is_empty() || param.contains(' ') || param.starts_with(':'))
+}
+
+impl PartialEq for Line {
+    fn eq(&self, other: &Self) -> bool {
+        self.tags == other.tags
+            && self.source == other.source
+            && self.command == other.command
+            && self.params == other.params
+            && self.has_trailing == other.has_trailing
+    }
+}
+
+impl Eq for Line {}
+
+impl std::fmt::Display for Line {
+    /// Formats this line as a raw IRC message, without the trailing
+    /// `\r\n`. Tags are written in key order with their values escaped
+    /// (tags in [`bare_tags`](Line::bare_tags) with empty values are
+    /// written without an `=`), and the last param is written as a
+    /// trailing param (with a leading colon) only when it needs to be,
+    /// or when [`has_trailing`](Line::has_trailing) is set.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_raw(f, &RawFormat::DISPLAY)
+    }
+}
+
+impl From<(&str, Vec<&str>)> for Line {
+    /// Creates a [`Line`] from a command and its params, with no tags or
+    /// source.
+    ///
+    /// # Example
+    /// ```
+    /// let line = ircparser::Line::from(("PRIVMSG", vec!["#rickastley", "Never gonna give you up!"]));
+    ///
+    /// assert_eq!(line.command, "PRIVMSG");
+    /// assert_eq!(line.params, vec!["#rickastley", "Never gonna give you up!"]);
+    /// ```
+    ///
+    fn from((command, params): (&str, Vec<&str>)) -> Self {
+        let params = params.into_iter().map(|x| x.to_string()).collect();
+        Self::new(HashMap::new(), None, command, params)
+    }
+}
+
+#[cfg(test)]
+mod test_line {
+    use super::Line;
+    use crate::{CaseMapping, ChannelVisibility, Severity, Source, TypingState};
+    use collection_macros::hashmap;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn line(command: &str, params: &[&str]) -> Line {
+        let params = params.iter().map(|x| x.to_string()).collect();
+        Line::new(Default::default(), None, command, params)
+    }
+
+    #[test]
+    fn test_channels_join() {
+        let line = line("JOIN", &["#a,#b"]);
+        assert_eq!(line.channels("#&"), vec!["#a", "#b"]);
+    }
+
+    #[test]
+    fn test_channels_part() {
+        let line = line("PART", &["#a", "Never gonna give you up!"]);
+        assert_eq!(line.channels("#&"), vec!["#a"]);
+    }
+
+    #[test]
+    fn test_channels_privmsg() {
+        let line = line("PRIVMSG", &["#a", "#b is a great channel"]);
+        assert_eq!(line.channels("#&"), vec!["#a"]);
+    }
+
+    #[test]
+    fn test_channels_trailing_text() {
+        let line = crate::parse("PRIVMSG #a :#foo")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        assert_eq!(line.channels("#&"), vec!["#a"]);
+
+        let line = crate::parse(":srv 332 rick #a :#foo")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        assert_eq!(line.channels("#&"), vec!["#a"]);
+    }
+
+    #[test]
+    fn test_channels_trailing_target() {
+        let line = crate::parse("JOIN :#a,#b").unwrap().pop_front().unwrap();
+        assert_eq!(line.channels("#&"), vec!["#a", "#b"]);
+
+        let line = crate::parse(":rick!u@h INVITE astley :#a")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        assert_eq!(line.channels("#&"), vec!["#a"]);
+    }
+
+    #[test]
+    fn test_merge_tags() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        line.tags = hashmap! {
+            String::from("id") => String::from("123"),
+            String::from("name") => String::from("rick"),
+        };
+
+        line.merge_tags(&hashmap! {
+            String::from("id") => String::from("456"),
+            String::from("account") => String::from("rick"),
+        });
+
+        assert_eq!(
+            line.tags,
+            hashmap! {
+                String::from("id") => String::from("456"),
+                String::from("name") => String::from("rick"),
+                String::from("account") => String::from("rick"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_with_tags() {
+        let line = line("PRIVMSG", &["#a", "hi"]);
+        let tagged = line.with_tags(&hashmap! {
+            String::from("msgid") => String::from("abc"),
+        });
+
+        assert!(line.tags.is_empty());
+        assert_eq!(&tagged.tags["msgid"], "abc");
+    }
+
+    #[test]
+    fn test_standard_reply_fail_with_context() {
+        let line = line(
+            "FAIL",
+            &[
+                "CHATHISTORY",
+                "INVALID_TARGET",
+                "LATEST",
+                "#a",
+                "Bad target",
+            ],
+        );
+        let reply = line.standard_reply().unwrap();
+
+        assert_eq!(reply.severity, Severity::Fail);
+        assert_eq!(reply.command, "CHATHISTORY");
+        assert_eq!(reply.code, "INVALID_TARGET");
+        assert_eq!(reply.context, vec!["LATEST", "#a"]);
+        assert_eq!(reply.description, "Bad target");
+    }
+
+    #[test]
+    fn test_standard_reply_note_without_context() {
+        let line = line("NOTE", &["*", "OPER_MESSAGE", "The server is restarting"]);
+        let reply = line.standard_reply().unwrap();
+
+        assert_eq!(reply.severity, Severity::Note);
+        assert_eq!(reply.command, "*");
+        assert_eq!(reply.code, "OPER_MESSAGE");
+        assert!(reply.context.is_empty());
+        assert_eq!(reply.description, "The server is restarting");
+    }
+
+    #[test]
+    fn test_standard_reply_other_command() {
+        let line = line("PRIVMSG", &["#a", "b", "c"]);
+        assert_eq!(line.standard_reply(), None);
+    }
+
+    #[test]
+    fn test_to_debug_string() {
+        let mut line = line(
+            "PRIVMSG",
+            &[
+                "#rickastley",
+                "Never gonna give you up, never gonna let you down",
+            ],
+        );
+        line.source = Some(String::from(":rick!user@host"));
+
+        assert_eq!(
+            line.to_debug_string(),
+            "PRIVMSG rick #rickastley \"Never gonna give you up, never g...\""
+        );
+    }
+
+    #[test]
+    fn test_to_debug_string_no_source() {
+        let line = line("PING", &[]);
+        assert_eq!(line.to_debug_string(), "PING *");
+    }
+
+    #[test]
+    fn test_names_bare() {
+        let line = line("353", &["me", "=", "#a", "@rick +nick user"]);
+        let names = line.names("~&@%+").unwrap();
+
+        assert_eq!(names.channel, "#a");
+        assert_eq!(names.members.len(), 3);
+        assert_eq!(names.members[0].prefixes, vec!['@']);
+        assert_eq!(names.members[0].source, Source::parse("rick"));
+        assert_eq!(names.members[1].prefixes, vec!['+']);
+        assert_eq!(names.members[1].source, Source::parse("nick"));
+        assert!(names.members[2].prefixes.is_empty());
+        assert_eq!(names.members[2].source, Source::parse("user"));
+    }
+
+    #[test]
+    fn test_names_userhost_in_names() {
+        let line = line(
+            "353",
+            &["me", "=", "#a", "@+rick!astley@never.gonna nick!user@host"],
+        );
+        let names = line.names("~&@%+").unwrap();
+
+        assert_eq!(names.members[0].prefixes, vec!['@', '+']);
+        assert_eq!(names.members[0].source.nick, "rick");
+        assert_eq!(names.members[0].source.user, Some(String::from("astley")));
+        assert_eq!(
+            names.members[0].source.host,
+            Some(String::from("never.gonna"))
+        );
+        assert_eq!(names.members[1].source.nick, "nick");
+        assert_eq!(names.members[1].source.user, Some(String::from("user")));
+        assert_eq!(names.members[1].source.host, Some(String::from("host")));
+    }
+
+    #[test]
+    fn test_param_refs() {
+        let line = line("PRIVMSG", &["#a", "hi"]);
+
+        assert_eq!(line.param_refs(), vec!["#a", "hi"]);
+        assert_eq!(line.param_refs()[..], ["#a", "hi"]);
+    }
+
+    #[test]
+    fn test_account_name_tag() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        assert_eq!(line.account_name(), None);
+
+        line.tags
+            .insert(String::from("account"), String::from("rick"));
+        assert_eq!(line.account_name(), Some("rick"));
+    }
+
+    #[test]
+    fn test_account_name_extended_join() {
+        let line = line("JOIN", &["#a", "rick", "Rick Astley"]);
+        assert_eq!(line.account_name(), Some("rick"));
+    }
+
+    #[test]
+    fn test_account_name_extended_join_logged_out() {
+        let line = line("JOIN", &["#a", "*", "Rick Astley"]);
+        assert_eq!(line.account_name(), None);
+    }
+
+    #[test]
+    fn test_rewrite_target() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        line.rewrite_target("#b");
+
+        assert_eq!(line.params, vec!["#b", "hi"]);
+    }
+
+    #[test]
+    fn test_rewrite_target_no_target() {
+        let mut line = line("PING", &["token"]);
+        line.rewrite_target("#b");
+
+        assert_eq!(line.params, vec!["token"]);
+    }
+
+    #[test]
+    fn test_to_string() {
+        let mut line = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        line.tags = hashmap! {
+            String::from("name") => String::from("rick"),
+            String::from("id") => String::from("123"),
+        };
+        line.source = Some(String::from(":nick!user@host"));
+
+        assert_eq!(
+            line.to_string(),
+            "@id=123;name=rick :nick!user@host PRIVMSG #rickastley :Never gonna give you up!"
+        );
+    }
+
+    #[test]
+    fn test_to_string_plain_last_param() {
+        let line = line("JOIN", &["#rickastley"]);
+        assert_eq!(line.to_string(), "JOIN #rickastley");
+    }
+
+    #[test]
+    fn test_to_escaped_log() {
+        let line = line("PRIVMSG", &["#a", "\x1b[2Jhi\x07\x02bold\x02\u{85}"]);
+        assert_eq!(
+            line.to_escaped_log(),
+            r"PRIVMSG #a \x1b[2Jhi\x07\x02bold\x02\x85"
+        );
+    }
+
+    #[test]
+    fn test_to_escaped_log_printable() {
+        let line = line("PRIVMSG", &["#a", "Never gonna give you up! 🎵"]);
+        assert_eq!(
+            line.to_escaped_log(),
+            "PRIVMSG #a :Never gonna give you up! 🎵"
+        );
+    }
+
+    #[test]
+    fn test_invite_info_command() {
+        let line = line("INVITE", &["nick", "#a"]);
+        assert_eq!(
+            line.invite_info(),
+            Some((String::from("nick"), String::from("#a")))
+        );
+    }
+
+    #[test]
+    fn test_invite_info_numeric() {
+        let line = line("341", &["me", "nick", "#a"]);
+        assert_eq!(
+            line.invite_info(),
+            Some((String::from("nick"), String::from("#a")))
+        );
+    }
+
+    #[test]
+    fn test_invite_info_other() {
+        assert_eq!(line("INVITE", &["nick"]).invite_info(), None);
+        assert_eq!(line("PRIVMSG", &["nick", "#a"]).invite_info(), None);
+    }
+
+    #[test]
+    fn test_reply_to() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        assert_eq!(line.reply_to(), None);
+
+        line.tags
+            .insert(String::from("+draft/reply"), String::from("abc"));
+        assert_eq!(line.reply_to(), Some("abc"));
+
+        line.tags
+            .insert(String::from("+reply"), String::from("def"));
+        assert_eq!(line.reply_to(), Some("def"));
+    }
+
+    #[test]
+    fn test_from_tuple() {
+        let line = Line::from(("PRIVMSG", vec!["#x", "hi"]));
+
+        assert!(line.tags.is_empty());
+        assert_eq!(line.source, None);
+        assert_eq!(line.command, "PRIVMSG");
+        assert_eq!(line.params, vec!["#x", "hi"]);
+    }
+
+    #[test]
+    fn test_from_tuple_no_params() {
+        let line: Line = ("PING", vec![]).into();
+
+        assert_eq!(line.command, "PING");
+        assert!(line.params.is_empty());
+    }
+
+    #[test]
+    fn test_set_tag() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+
+        line.set_tag("id", "123");
+        assert_eq!(&line.tags["id"], "123");
+
+        line.set_tag("id", "456");
+        assert_eq!(&line.tags["id"], "456");
+        assert_eq!(line.tags.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_tag() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        line.set_tag("id", "123");
+
+        assert_eq!(line.remove_tag("id"), Some(String::from("123")));
+        assert_eq!(line.remove_tag("id"), None);
+        assert!(line.tags.is_empty());
+    }
+
+    #[test]
+    fn test_chghost_info() {
+        let mut line = line("CHGHOST", &["newuser", "new.host"]);
+        line.source = Some(String::from(":nick!user@old.host"));
+
+        assert_eq!(
+            line.chghost_info(),
+            Some((String::from("newuser"), String::from("new.host")))
+        );
+        assert_eq!(line.source_nick(), Some("nick"));
+    }
+
+    #[test]
+    fn test_chghost_info_missing_params() {
+        let line = line("CHGHOST", &["newuser"]);
+        assert_eq!(line.chghost_info(), None);
+    }
+
+    #[test]
+    fn test_references_channel_rfc1459() {
+        let line = line("PRIVMSG", &["#Foo[]", "hi"]);
+
+        assert!(line.references_channel("#foo{}", "#&", CaseMapping::Rfc1459));
+        assert!(!line.references_channel("#foo{}", "#&", CaseMapping::Ascii));
+        assert!(!line.references_channel("#bar", "#&", CaseMapping::Rfc1459));
+    }
+
+    #[test]
+    fn test_is_from_bot() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        assert!(!line.is_from_bot());
+
+        line.set_tag("bot", "");
+        assert!(line.is_from_bot());
+    }
+
+    #[test]
+    fn test_is_from_bot_draft() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        line.set_tag("draft/bot", "");
+
+        assert!(line.is_from_bot());
+    }
+
+    #[test]
+    fn test_typing_state() {
+        let mut line = line("TAGMSG", &["#a"]);
+        assert_eq!(line.typing_state(), None);
+
+        for (value, state) in [
+            ("active", TypingState::Active),
+            ("paused", TypingState::Paused),
+            ("done", TypingState::Done),
+        ] {
+            line.set_tag("+typing", value);
+            assert_eq!(line.typing_state(), Some(state));
+        }
+
+        line.set_tag("+typing", "dancing");
+        assert_eq!(line.typing_state(), None);
+    }
+
+    #[test]
+    fn test_with_label() {
+        let line = line("PRIVMSG", &["#a", "hi there"]);
+        assert_eq!(line.label(), None);
+
+        let labelled = line.with_label("abc");
+        assert_eq!(labelled.label(), Some("abc"));
+        assert_eq!(labelled.to_string(), "@label=abc PRIVMSG #a :hi there");
+        assert_eq!(line.label(), None);
+    }
+
+    #[test]
+    fn test_whois_idle() {
+        let line = line(
+            "317",
+            &[
+                "me",
+                "rick",
+                "42",
+                "1609459200",
+                "seconds idle, signon time",
+            ],
+        );
+        assert_eq!(line.whois_idle(), Some((42, 1609459200)));
+    }
+
+    #[test]
+    fn test_whois_idle_malformed() {
+        let malformed = line(
+            "317",
+            &["me", "rick", "forty-two", "1609459200", "seconds idle"],
+        );
+        assert_eq!(malformed.whois_idle(), None);
+
+        let other = line("318", &["me", "rick", "42", "1609459200", "seconds idle"]);
+        assert_eq!(other.whois_idle(), None);
+    }
+
+    #[test]
+    fn test_monitor_online() {
+        let line = line("730", &["me", "rick!astley@never.gonna,nick!user@host"]);
+        let (online, sources) = line.monitor_notification().unwrap();
+
+        assert!(online);
+        assert_eq!(
+            sources,
+            vec![
+                Source::parse("rick!astley@never.gonna"),
+                Source::parse("nick!user@host"),
+            ]
+        );
+        assert_eq!(sources[0].host, Some(String::from("never.gonna")));
+    }
+
+    #[test]
+    fn test_monitor_offline() {
+        let line = line("731", &["me", "rick,nick"]);
+        let (online, sources) = line.monitor_notification().unwrap();
+
+        assert!(!online);
+        assert_eq!(sources, vec![Source::parse("rick"), Source::parse("nick")]);
+    }
+
+    #[test]
+    fn test_setname() {
+        let mut line = line("SETNAME", &["Rick Astley"]);
+        line.source = Some(String::from(":rick!user@host"));
+
+        assert_eq!(line.setname(), Some(String::from("Rick Astley")));
+        assert_eq!(line.source_nick(), Some("rick"));
+    }
+
+    #[test]
+    fn test_setname_other() {
+        let line = line("PRIVMSG", &["#a", "Rick Astley"]);
+        assert_eq!(line.setname(), None);
+    }
+
+    #[test]
+    fn test_content_eq() {
+        let mut a = line("PRIVMSG", &["#a", "hi"]);
+        let mut b = a.clone();
+        a.set_tag("msgid", "abc");
+        b.set_tag("time", "2021-01-01T00:00:00.000Z");
+
+        assert!(a.content_eq(&b));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_content_ne() {
+        let a = line("PRIVMSG", &["#a", "hi"]);
+        let mut b = line("PRIVMSG", &["#a", "hi"]);
+        b.source = Some(String::from(":rick!user@host"));
+
+        assert!(!a.content_eq(&b));
+        assert!(!a.content_eq(&line("PRIVMSG", &["#a", "bye"])));
+    }
+
+    #[test]
+    fn test_wallops_text() {
+        assert_eq!(
+            line("WALLOPS", &["Server restarting"]).wallops_text(),
+            Some("Server restarting")
+        );
+        assert_eq!(
+            line("GLOBOPS", &["Server restarting"]).wallops_text(),
+            Some("Server restarting")
+        );
+        assert_eq!(line("PRIVMSG", &["#a", "hi"]).wallops_text(), None);
+    }
+
+    #[test]
+    fn test_is_from() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        assert!(!line.is_from("rick", CaseMapping::Rfc1459));
+
+        line.source = Some(String::from(":Rick!user@host"));
+        assert!(line.is_from("rick", CaseMapping::Rfc1459));
+        assert!(line.is_from("RICK", CaseMapping::Ascii));
+        assert!(!line.is_from("nick", CaseMapping::Rfc1459));
+        assert!(!line.is_from("user", CaseMapping::Rfc1459));
+    }
+
+    #[test]
+    fn test_reaction() {
+        let mut line = line("TAGMSG", &["#a"]);
+        line.set_tag("+draft/reply", "abc");
+        assert_eq!(line.reaction(), None);
+
+        line.set_tag("+draft/react", "👍");
+        assert_eq!(line.reaction(), Some(("abc", "👍")));
+
+        line.remove_tag("+draft/reply");
+        assert_eq!(line.reaction(), None);
+    }
+
+    #[test]
+    fn test_default_to_string() {
+        let line = Line::default();
+
+        assert_eq!(line.to_string(), "");
+        assert_eq!(
+            line.try_to_string().unwrap_err().details,
+            "invalid command \"\""
+        );
+    }
+
+    #[test]
+    fn test_try_to_string() {
+        assert_eq!(
+            line("PRIVMSG", &["#a", "hi there"])
+                .try_to_string()
+                .unwrap(),
+            "PRIVMSG #a :hi there"
+        );
+        assert!(line("PRIVMSG", &["#a b", "hi"]).try_to_string().is_err());
+        assert!(line("PRIVMSG", &["", "hi"]).try_to_string().is_err());
+        assert!(line("PRIVMSG", &["#a", "hi\r\nQUIT"])
+            .try_to_string()
+            .is_err());
+    }
+
+    #[test]
+    fn test_try_to_string_invalid_command() {
+        for command in ["PRIVMSG\r\nQUIT", "PRIVMSG\0", ":X", "@X"] {
+            let msg = line(command, &["#a"]);
+            assert!(msg.try_to_string().is_err(), "{command:?}");
+        }
+    }
+
+    #[test]
+    fn test_try_to_string_invalid_tags() {
+        for (key, value) in [("a b", "c"), ("a\r\nQUIT", "c"), ("", "c"), ("a", "b\0")] {
+            let mut msg = line("PING", &["x"]);
+            msg.set_tag(key, value);
+            assert!(msg.try_to_string().is_err(), "{key:?}");
+        }
+
+        // Newlines in values are escaped, so can't break the line.
+        let mut msg = line("PING", &["x"]);
+        msg.set_tag("a", "b\r\nc");
+        assert_eq!(msg.try_to_string().unwrap(), "@a=b\\r\\nc PING x");
+    }
+
+    #[test]
+    fn test_try_to_string_invalid_source() {
+        for source in ["a b", ":a b", "a\r\nQUIT", "a\0", ":"] {
+            let msg = line("PING", &["x"]).with_source(source);
+            assert!(msg.try_to_string().is_err(), "{source:?}");
+        }
+
+        let msg = line("PING", &["x"]).with_source("nick!user@host");
+        assert_eq!(msg.try_to_string().unwrap(), ":nick!user@host PING x");
+    }
+
+    #[test]
+    fn test_join_targets_keyed() {
+        let line = line("JOIN", &["#a,#b,#c", "key1,key2"]);
+
+        assert_eq!(
+            line.join_targets(),
+            vec![
+                (String::from("#a"), Some(String::from("key1"))),
+                (String::from("#b"), Some(String::from("key2"))),
+                (String::from("#c"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_targets_unkeyed() {
+        let line = line("JOIN", &["#a,#b"]);
+
+        assert_eq!(
+            line.join_targets(),
+            vec![(String::from("#a"), None), (String::from("#b"), None)]
+        );
+    }
+
+    #[test]
+    fn test_join_targets_part_all() {
+        assert!(line("JOIN", &["0"]).join_targets().is_empty());
+        assert!(line("PART", &["#a"]).join_targets().is_empty());
+    }
+
+    #[test]
+    fn test_make_pong_client() {
+        let ping = line("PING", &["LAG123"]);
+
+        assert!(ping.is_ping());
+        assert_eq!(ping.make_pong(), Some(line("PONG", &["LAG123"])));
+    }
+
+    #[test]
+    fn test_make_pong_server() {
+        let ping = line("PING", &["server1.net", "server2.net"]);
+
+        assert!(ping.is_ping());
+        assert_eq!(
+            ping.make_pong(),
+            Some(line("PONG", &["server2.net", "server1.net"]))
+        );
+    }
+
+    #[test]
+    fn test_make_pong_not_ping() {
+        let pong = line("PONG", &["LAG123"]);
+
+        assert!(!pong.is_ping());
+        assert_eq!(pong.make_pong(), None);
+    }
+
+    #[test]
+    fn test_components() {
+        let mut line = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        line.set_tag("id", "123");
+        line.set_tag("name", "rick astley");
+        line.source = Some(String::from(":nick!user@host"));
+
+        assert_eq!(
+            line.components(),
+            vec![
+                "id=123;name=rick\\sastley",
+                "nick!user@host",
+                "PRIVMSG",
+                "#rickastley",
+                "Never gonna give you up!",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_server_time_iso() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+        assert_eq!(line.server_time(), None);
+
+        line.set_tag("time", "2021-01-01T00:00:00.500Z");
+        assert_eq!(
+            line.server_time(),
+            Some(UNIX_EPOCH + Duration::from_millis(1609459200500))
+        );
+    }
+
+    #[test]
+    fn test_server_time_unix() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+
+        line.set_tag("t", "1609459200");
+        assert_eq!(
+            line.server_time(),
+            Some(UNIX_EPOCH + Duration::from_secs(1609459200))
+        );
+
+        line.set_tag("t", "yesterday");
+        assert_eq!(line.server_time(), None);
+    }
+
+    #[test]
+    fn test_server_time_malformed_iso() {
+        let mut line = line("PRIVMSG", &["#a", "hi"]);
+
+        line.set_tag("time", "yesterday");
+        assert_eq!(line.server_time(), None);
+
+        line.set_tag("t", "1609459200");
+        assert_eq!(
+            line.server_time(),
+            Some(UNIX_EPOCH + Duration::from_secs(1609459200))
+        );
+    }
+
+    #[test]
+    fn test_is_direct_to_channel() {
+        let line = line("PRIVMSG", &["#rick", "hi"]);
+        assert!(!line.is_direct_to("#rick", "#&", CaseMapping::Rfc1459));
+    }
+
+    #[test]
+    fn test_is_direct_to_me() {
+        let line = line("PRIVMSG", &["Rick", "hi"]);
+        assert!(line.is_direct_to("rick", "#&", CaseMapping::Rfc1459));
+    }
+
+    #[test]
+    fn test_is_direct_to_someone_else() {
+        let line = line("PRIVMSG", &["nick", "hi"]);
+        assert!(!line.is_direct_to("rick", "#&", CaseMapping::Rfc1459));
+    }
+
+    #[test]
+    fn test_is_direct_to_notice() {
+        let line = line("NOTICE", &["rick", "hi"]);
+        assert!(!line.is_direct_to("rick", "#&", CaseMapping::Rfc1459));
+    }
+
+    #[test]
+    fn test_names_visibility() {
+        for (symbol, visibility) in [
+            ("=", ChannelVisibility::Public),
+            ("*", ChannelVisibility::Private),
+            ("@", ChannelVisibility::Secret),
+        ] {
+            let names = line("353", &["me", symbol, "#a", "@rick"])
+                .names("@+")
+                .unwrap();
+
+            assert_eq!(names.channel, "#a");
+            assert_eq!(names.visibility, Some(visibility));
+            assert_eq!(names.members[0].source.nick, "rick");
+        }
+    }
+
+    #[test]
+    fn test_names_no_visibility() {
+        let names = line("353", &["me", "#a", "@rick"]).names("@+").unwrap();
+
+        assert_eq!(names.channel, "#a");
+        assert_eq!(names.visibility, None);
+        assert_eq!(names.members.len(), 1);
+    }
+
+    #[test]
+    fn test_to_twitch_string_privmsg() {
+        let msg = "@badge-info=;badges=broadcaster/1;color=#FF0000;display-name=Rick;emotes=;id=b34ccfc7-4977-403a-8a94-33c6bac34fb8;mod=0;room-id=1337;subscriber=0;tmi-sent-ts=1507246572675;turbo=0;user-id=1337;user-type= :rick!rick@rick.tmi.twitch.tv PRIVMSG #rickastley :Kappa";
+        let line = crate::parse(msg).unwrap().pop_front().unwrap();
+
+        assert_eq!(line.to_twitch_string(), msg);
+    }
+
+    #[test]
+    fn test_to_twitch_string_join() {
+        let msg = ":rick!rick@rick.tmi.twitch.tv JOIN #rickastley";
+        let line = crate::parse(msg).unwrap().pop_front().unwrap();
+
+        assert_eq!(line.to_twitch_string(), msg);
+    }
+
+    #[test]
+    fn test_to_twitch_string_ping() {
+        assert_eq!(
+            line("PING", &["tmi.twitch.tv"]).to_twitch_string(),
+            "PING :tmi.twitch.tv"
+        );
+    }
+
+    #[test]
+    fn test_is_netsplit_quit() {
+        let line = line("QUIT", &["irc.example.com hub.example.com"]);
+        assert!(line.is_netsplit_quit());
+    }
+
+    #[test]
+    fn test_is_netsplit_quit_normal_reason() {
+        assert!(!line("QUIT", &["Never gonna give you up!"]).is_netsplit_quit());
+        assert!(!line("QUIT", &["See you soon..."]).is_netsplit_quit());
+        assert!(!line("QUIT", &["Ping timeout: 120 seconds"]).is_netsplit_quit());
+        assert!(!line("QUIT", &[]).is_netsplit_quit());
+    }
+
+    #[test]
+    fn test_is_netsplit_quit_other_command() {
+        let line = line("PART", &["irc.example.com hub.example.com"]);
+        assert!(!line.is_netsplit_quit());
+    }
+
+    #[test]
+    fn test_nick_change() {
+        let mut nick = line("NICK", &["astley"]);
+        nick.source = Some(":rick!user@host".to_string());
+
+        assert_eq!(
+            nick.nick_change(),
+            Some(("rick".to_string(), "astley".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_nick_change_no_source() {
+        assert_eq!(line("NICK", &["astley"]).nick_change(), None);
+        assert_eq!(line("PRIVMSG", &["astley", "hi"]).nick_change(), None);
+    }
+
+    #[test]
+    fn test_channel_mode_is() {
+        let line = line("324", &["rick", "#rickastley", "+knt", "never"]);
+
+        assert_eq!(
+            line.channel_mode_is(),
+            Some(("#rickastley".to_string(), "+knt never".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_channel_mode_is_other() {
+        let line = line("329", &["rick", "#rickastley", "1656000000"]);
+        assert_eq!(line.channel_mode_is(), None);
+    }
+
+    #[test]
+    fn test_channel_creation_time() {
+        let line = line("329", &["rick", "#rickastley", "1656000000"]);
+
+        assert_eq!(
+            line.channel_creation_time(),
+            Some((
+                "#rickastley".to_string(),
+                UNIX_EPOCH + Duration::from_secs(1656000000)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_channel_creation_time_malformed() {
+        let line = line("329", &["rick", "#rickastley", "yesterday"]);
+        assert_eq!(line.channel_creation_time(), None);
+    }
+
+    #[test]
+    fn test_channel_context() {
+        let mut msg = line("PRIVMSG", &["rick", "Never gonna give you up!"]);
+        msg.set_tag("+draft/channel-context", "#rickastley");
+
+        assert_eq!(msg.channel_context(), Some("#rickastley"));
+    }
+
+    #[test]
+    fn test_channel_context_missing() {
+        let msg = line("PRIVMSG", &["rick", "Never gonna give you up!"]);
+        assert_eq!(msg.channel_context(), None);
+    }
+
+    #[test]
+    fn test_action_text() {
+        let action = line("PRIVMSG", &["#rickastley", "\x01ACTION dances\x01"]);
+        assert!(action.is_action());
+        assert_eq!(action.action_text(), Some("dances"));
+
+        let unterminated = line("PRIVMSG", &["#rickastley", "\x01ACTION dances"]);
+        assert_eq!(unterminated.action_text(), Some("dances"));
+    }
+
+    #[test]
+    fn test_action_text_not_action() {
+        for text in [
+            "Never gonna give you up!",
+            "\x01VERSION\x01",
+            "\x01ACTIONS\x01",
+        ] {
+            let msg = line("PRIVMSG", &["#rickastley", text]);
+            assert!(!msg.is_action());
+            assert_eq!(msg.action_text(), None);
+        }
+
+        let notice = line("NOTICE", &["#rickastley", "\x01ACTION dances\x01"]);
+        assert!(!notice.is_action());
+    }
+
+    #[test]
+    fn test_parse_tagmsg() {
+        let msg = "@+draft/react=👍;+reply=abc123 :rick!user@host TAGMSG #rickastley";
+        let tagmsg = crate::parse(msg).unwrap().pop_front().unwrap();
+
+        assert!(tagmsg.is_tagmsg());
+        assert_eq!(tagmsg.params, vec!["#rickastley"]);
+        assert_eq!(tagmsg.to_string(), msg);
+    }
+
+    #[test]
+    fn test_build_tagmsg() {
+        let mut tagmsg = Line::tagmsg("#rickastley");
+        tagmsg.set_tag("+typing", "paused");
+
+        assert!(tagmsg.is_tagmsg());
+        assert_eq!(
+            tagmsg.try_to_string().unwrap(),
+            "@+typing=paused TAGMSG #rickastley"
+        );
+        assert!(!line("PRIVMSG", &["#rickastley", "hi"]).is_tagmsg());
+    }
+
+    #[test]
+    fn test_require_params() {
+        let msg = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+
+        assert!(msg.require_params(0).is_ok());
+        assert!(msg.require_params(2).is_ok());
+    }
+
+    #[test]
+    fn test_require_params_insufficient() {
+        let e = line("PRIVMSG", &["#rickastley"])
+            .require_params(2)
+            .unwrap_err();
+        assert_eq!(e.details, "PRIVMSG requires at least 2 params, but has 1");
+    }
+
+    #[test]
+    fn test_welcome_nick() {
+        let msg = line(
+            "001",
+            &["rick_", "Welcome to the Example IRC Network, rick_"],
+        );
+        assert_eq!(msg.welcome_nick(), Some("rick_"));
+    }
+
+    #[test]
+    fn test_welcome_nick_other() {
+        let msg = line("002", &["rick", "Your host is irc.example.com"]);
+        assert_eq!(msg.welcome_nick(), None);
+    }
+
+    #[test]
+    fn test_list_entry() {
+        let msg = line(
+            "322",
+            &["rick", "#rickastley", "42", "Never gonna give you up!"],
+        );
+
+        assert_eq!(
+            msg.list_entry(),
+            Some((
+                "#rickastley".to_string(),
+                42,
+                "Never gonna give you up!".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_list_entry_no_topic() {
+        let msg = line("322", &["rick", "#rickastley", "3"]);
+        assert_eq!(
+            msg.list_entry(),
+            Some(("#rickastley".to_string(), 3, String::new()))
+        );
+    }
+
+    #[test]
+    fn test_list_entry_other() {
+        assert_eq!(line("323", &["rick", "End of /LIST"]).list_entry(), None);
+        assert_eq!(
+            line("322", &["rick", "#rickastley", "many"]).list_entry(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_cap() {
+        assert!(line("CAP", &["*", "LS", "multi-prefix sasl"]).is_cap());
+        assert!(line("cap", &["REQ", "sasl"]).is_cap());
+        assert!(!line("AUTHENTICATE", &["PLAIN"]).is_cap());
+    }
+
+    #[test]
+    fn test_is_authenticate() {
+        assert!(line("AUTHENTICATE", &["+"]).is_authenticate());
+        assert!(!line("CAP", &["END"]).is_authenticate());
+    }
+
+    #[test]
+    fn test_error_message() {
+        let msg = line("ERROR", &["Closing link: rick (Ping timeout)"]);
+        assert_eq!(
+            msg.error_message(),
+            Some("Closing link: rick (Ping timeout)")
+        );
+    }
+
+    #[test]
+    fn test_error_message_other() {
+        assert_eq!(line("QUIT", &["bye"]).error_message(), None);
+        assert_eq!(line("ERROR", &[]).error_message(), None);
+    }
+
+    #[test]
+    fn test_statusmsg_prefix() {
+        let msg = line("PRIVMSG", &["@#rickastley", "Never gonna give you up!"]);
+        assert_eq!(msg.statusmsg_prefix("@+"), Some(('@', "#rickastley")));
+
+        let msg = line("NOTICE", &["+#rickastley", "Never gonna let you down!"]);
+        assert_eq!(msg.statusmsg_prefix("@+"), Some(('+', "#rickastley")));
+    }
+
+    #[test]
+    fn test_statusmsg_prefix_plain() {
+        let msg = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        assert_eq!(msg.statusmsg_prefix("@+"), None);
+
+        let msg = line("PRIVMSG", &["@", "Never gonna give you up!"]);
+        assert_eq!(msg.statusmsg_prefix("@+"), None);
+    }
+
+    #[test]
+    fn test_account_notify_login() {
+        let msg = line("ACCOUNT", &["astley"]);
+        assert_eq!(msg.account_notify(), Some(Some("astley".to_string())));
+    }
+
+    #[test]
+    fn test_account_notify_logout() {
+        assert_eq!(line("ACCOUNT", &["*"]).account_notify(), Some(None));
+        assert_eq!(line("ACCOUNT", &[]).account_notify(), None);
+        assert_eq!(line("AWAY", &["astley"]).account_notify(), None);
+    }
+
+    #[test]
+    fn test_to_string_bare_tags() {
+        for msg in ["@a;b=c PING :x", "@a=;b=c PING :x"] {
+            let parsed = crate::parse(msg).unwrap().pop_front().unwrap();
+            assert_eq!(parsed.to_string(), msg);
+        }
+    }
+
+    #[test]
+    fn test_bare_tags_ignored_by_eq() {
+        let bare = crate::parse("@a PING x").unwrap().pop_front().unwrap();
+        let explicit = crate::parse("@a= PING x").unwrap().pop_front().unwrap();
+
+        assert_eq!(bare, explicit);
+        assert_ne!(bare.to_string(), explicit.to_string());
+    }
+
+    #[test]
+    fn test_set_tag_clears_bare() {
+        let mut msg = crate::parse("@a;b PING :x").unwrap().pop_front().unwrap();
+        msg.set_tag("a", "");
+
+        assert_eq!(msg.to_string(), "@a=;b PING :x");
+    }
+
+    #[test]
+    fn test_to_twitch_string_empty_tags() {
+        let mut msg = line("PRIVMSG", &["#rickastley", "Kappa"]);
+        msg.set_tag("emotes", "");
+
+        assert_eq!(
+            msg.to_twitch_string(),
+            "@emotes= PRIVMSG #rickastley :Kappa"
+        );
+    }
+
+    #[test]
+    fn test_with_source() {
+        let msg = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        let relayed = msg.with_source("rick!user@host");
+
+        assert_eq!(msg.source, None);
+        assert_eq!(relayed.source.as_deref(), Some(":rick!user@host"));
+        assert_eq!(
+            relayed.to_string(),
+            ":rick!user@host PRIVMSG #rickastley :Never gonna give you up!"
+        );
+    }
+
+    #[test]
+    fn test_with_source_overwrite() {
+        let msg = line("PING", &["x"]).with_source(":old.server");
+        let relayed = msg.with_source(":new.server");
+
+        assert_eq!(relayed.source.as_deref(), Some(":new.server"));
+        assert_eq!(relayed.to_string(), ":new.server PING x");
+    }
+
+    #[test]
+    fn test_pong_token() {
+        assert_eq!(
+            line("PONG", &["server", "LAG123"]).pong_token(),
+            Some("LAG123")
+        );
+        assert_eq!(line("PONG", &["LAG123"]).pong_token(), Some("LAG123"));
+    }
+
+    #[test]
+    fn test_pong_token_other() {
+        assert_eq!(line("PING", &["LAG123"]).pong_token(), None);
+        assert_eq!(line("PONG", &[]).pong_token(), None);
+    }
+
+    #[test]
+    fn test_identity_msgid() {
+        let mut msg = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        msg.set_tag("msgid", "abc123");
+
+        assert_eq!(msg.identity(), "abc123");
+    }
+
+    #[test]
+    fn test_identity_hash() {
+        let mut a = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        a.source = Some(":rick!user@host".to_string());
+        a.set_tag("time", "2022-06-23T16:00:00.000Z");
+
+        let mut b = a.with_source("rick!user@host");
+        b.set_tag("label", "xyz");
+        assert_eq!(a.identity(), b.identity());
+        assert_eq!(a.identity(), "b9c65d5cb717ff47");
+
+        b.set_tag("time", "2022-06-23T16:00:01.000Z");
+        assert_ne!(a.identity(), b.identity());
+    }
+
+    #[test]
+    fn test_identity_param_boundaries() {
+        let a = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        let b = line("PRIVMSG", &["#rickastley Never", "gonna give you up!"]);
+
+        assert_ne!(a.identity(), b.identity());
+    }
+
+    #[test]
+    fn test_rewrite_source_nick() {
+        let mut msg = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        msg.source = Some(":rick!user@host".to_string());
+        msg.rewrite_source_nick("astley|relay");
+
+        assert_eq!(msg.source.as_deref(), Some(":astley|relay!user@host"));
+    }
+
+    #[test]
+    fn test_rewrite_source_nick_partial() {
+        let mut msg = line("PRIVMSG", &["#rickastley", "Never gonna give you up!"]);
+        msg.source = Some(":rick@host".to_string());
+        msg.rewrite_source_nick("astley");
+        assert_eq!(msg.source.as_deref(), Some(":astley@host"));
+
+        let mut msg = line("PING", &["x"]);
+        msg.rewrite_source_nick("astley");
+        assert_eq!(msg.source, None);
+    }
+
+    #[test]
+    fn test_is_from_server() {
+        for source in [":irc.example.com", "irc.example.com", ":42X"] {
+            let mut msg = line("NOTICE", &["*", "*** Looking up your hostname..."]);
+            msg.source = Some(source.to_string());
+            assert!(msg.is_from_server());
+        }
+    }
+
+    #[test]
+    fn test_is_from_server_user() {
+        for source in [":rick!user@host", ":rick@host.example.com", ":rick"] {
+            let mut msg = line("NOTICE", &["astley", "Never gonna give you up!"]);
+            msg.source = Some(source.to_string());
+            assert!(!msg.is_from_server());
+        }
+
+        assert!(!line("NOTICE", &["astley", "hi"]).is_from_server());
+    }
+
+    #[test]
+    fn test_whois_channels() {
+        let msg = line("319", &["me", "rick", "@+#rickastley #never ~&#gonna "]);
+
+        assert_eq!(
+            msg.whois_channels("~&@%+", "#"),
+            vec![
+                ("#rickastley".to_string(), vec!['@', '+']),
+                ("#never".to_string(), vec![]),
+                ("#gonna".to_string(), vec!['~', '&']),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whois_channels_other() {
+        let msg = line("318", &["me", "rick", "End of /WHOIS list."]);
+        assert!(msg.whois_channels("@+", "#&").is_empty());
+    }
+
+    #[test]
+    fn test_whois_channels_ampersand() {
+        let msg = line("319", &["me", "rick", "@&local ~@#rickastley &never"]);
+
+        assert_eq!(
+            msg.whois_channels("~&@%+", "#&"),
+            vec![
+                ("&local".to_string(), vec!['@']),
+                ("#rickastley".to_string(), vec!['~', '@']),
+                ("&never".to_string(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trailing() {
+        let msg = crate::parse("PRIVMSG #rickastley :Never")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+
+        assert!(msg.has_trailing);
+        assert_eq!(msg.trailing(), Some("Never"));
+        assert_eq!(msg.to_string(), "PRIVMSG #rickastley :Never");
+    }
+
+    #[test]
+    fn test_trailing_plain_last_param() {
+        let msg = crate::parse("PRIVMSG #rickastley Never")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+
+        assert!(!msg.has_trailing);
+        assert_eq!(msg.trailing(), None);
+        assert_eq!(msg.params.last().map(|x| x.as_str()), Some("Never"));
+        assert_eq!(line("JOIN", &["#rickastley"]).trailing(), None);
+    }
+
+    #[test]
+    fn test_trailing_affects_equality() {
+        let trailing = crate::parse("PING :x").unwrap().pop_front().unwrap();
+        let plain = crate::parse("PING x").unwrap().pop_front().unwrap();
+
+        assert_eq!(trailing.params, plain.params);
+        assert_ne!(trailing, plain);
+        assert!(crate::reparse_equals("PING :x\r\nPING x\r\n"));
+    }
+
+    #[test]
+    fn test_trailing_empty() {
+        let msg = crate::parse("PRIVMSG #rickastley :")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        assert_eq!(msg.trailing(), Some(""));
+    }
+
+    #[test]
+    fn test_server_notice_auth() {
+        let msg = line("NOTICE", &["AUTH", "*** Checking Ident"]);
+
+        assert!(msg.is_server_notice());
+        assert_eq!(msg.server_notice_text(), Some("*** Checking Ident"));
+    }
+
+    #[test]
+    fn test_server_notice_star() {
+        let msg = line("NOTICE", &["*", "*** Notice -- Client connecting"])
+            .with_source("irc.example.com");
+
+        assert!(msg.is_server_notice());
+        assert_eq!(
+            msg.server_notice_text(),
+            Some("*** Notice -- Client connecting")
+        );
+    }
+
+    #[test]
+    fn test_server_notice_from_user() {
+        let msg = line("NOTICE", &["*", "Never gonna give you up!"]).with_source("rick!user@host");
+        assert!(!msg.is_server_notice());
+        assert_eq!(msg.server_notice_text(), None);
+
+        let msg = line("NOTICE", &["rick", "*** Notice -- Client connecting"])
+            .with_source("irc.example.com");
+        assert!(!msg.is_server_notice());
+    }
+
+    #[test]
+    fn test_push_kick() {
+        let mut kick = line("KICK", &[]);
+        kick.push_param("#rickastley").unwrap();
+        kick.push_param("rick").unwrap();
+        kick.push_trailing("Never gonna give you up!").unwrap();
+
+        assert_eq!(
+            kick.params,
+            vec!["#rickastley", "rick", "Never gonna give you up!"]
+        );
+        assert_eq!(
+            kick.try_to_string().unwrap(),
+            "KICK #rickastley rick :Never gonna give you up!"
+        );
+    }
+
+    #[test]
+    fn test_push_privmsg() {
+        let mut msg = line("PRIVMSG", &[]);
+        msg.push_param("#rickastley").unwrap();
+        msg.push_trailing("hi").unwrap();
+
+        assert_eq!(msg.trailing(), Some("hi"));
+        assert_eq!(msg.to_string(), "PRIVMSG #rickastley :hi");
+
+        msg.push_param("there").unwrap();
+        assert_eq!(msg.trailing(), None);
+        assert_eq!(msg.to_string(), "PRIVMSG #rickastley hi there");
+    }
+
+    #[test]
+    fn test_push_param_round_trip() {
+        let mut msg = line("MODE", &[]);
+        msg.push_param("#rickastley").unwrap();
+        msg.push_param("+o").unwrap();
+        msg.push_param("rick").unwrap();
+
+        let parsed = crate::parse(&msg.to_string()).unwrap().pop_front().unwrap();
+        assert_eq!(parsed, msg);
+    }
+
+    #[test]
+    fn test_push_param_invalid() {
+        let mut msg = line("PRIVMSG", &["#rickastley"]);
+
+        for param in ["", "a b", ":a", "a\r\nQUIT"] {
+            assert!(msg.push_param(param).is_err());
+        }
+        assert_eq!(msg.params, vec!["#rickastley"]);
+
+        for text in ["hi\r\nQUIT :pwned", "hi\nQUIT", "hi\0"] {
+            assert!(msg.push_trailing(text).is_err());
+        }
+        assert_eq!(msg.params, vec!["#rickastley"]);
+
+        msg.push_trailing("Never gonna give you up!").unwrap();
+        assert!(msg.push_param("rick").is_err());
+        assert_eq!(
+            msg.to_string(),
+            "PRIVMSG #rickastley :Never gonna give you up!"
+        );
+    }
 }
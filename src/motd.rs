@@ -0,0 +1,148 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::Line;
+
+/// A builder which collects the lines of a server's message of the day.
+/// The MOTD is sent as an `RPL_MOTDSTART` (`375`), followed by a
+/// `RPL_MOTD` (`372`) for each line, and ended by an `RPL_ENDOFMOTD`
+/// (`376`).
+///
+/// # Example
+/// ```
+/// let msg = ":irc.example.com 375 rick :- irc.example.com Message of the day -\r\n\
+///            :irc.example.com 372 rick :- Never gonna give you up!\r\n\
+///            :irc.example.com 372 rick :- Never gonna let you down!\r\n\
+///            :irc.example.com 376 rick :End of /MOTD command.\r\n";
+///
+/// let mut builder = ircparser::MotdBuilder::new();
+/// let motd = ircparser::parse(msg).unwrap().iter().find_map(|x| builder.push(x));
+///
+/// assert_eq!(motd.unwrap(), "Never gonna give you up!\nNever gonna let you down!");
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct MotdBuilder {
+    lines: Vec<String>,
+}
+
+impl MotdBuilder {
+    /// Creates a new [`MotdBuilder`].
+    ///
+    /// # Returns
+    /// - [`MotdBuilder`] - The new [`MotdBuilder`] instance.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a line to this builder. Lines other than `375`, `372`, and
+    /// `376` replies are ignored. The `- ` prefix servers put before
+    /// each MOTD line is removed.
+    ///
+    /// # Arguments
+    /// - `line` - The line to feed.
+    ///
+    /// # Returns
+    /// - [`Option<String>`] - The full MOTD, with its lines joined by
+    ///   newlines, if this line ended it. The builder is then reset, so
+    ///   it can be reused for a later MOTD.
+    ///
+    pub fn push(&mut self, line: &Line) -> Option<String> {
+        match line.command.as_str() {
+            "375" => self.lines.clear(),
+            "372" => {
+                let text = line.params.last()?;
+                let text = text
+                    .strip_prefix("- ")
+                    .or_else(|| text.strip_prefix('-'))
+                    .unwrap_or(text);
+                self.lines.push(text.to_string());
+            }
+            "376" => {
+                let motd = self.lines.join("\n");
+                self.lines.clear();
+                return Some(motd);
+            }
+            _ => {}
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test_motd {
+    use super::MotdBuilder;
+    use crate::Line;
+
+    fn reply(command: &str, text: &str) -> Line {
+        Line::from((command, vec!["rick", text]))
+    }
+
+    #[test]
+    fn test_motd() {
+        let mut builder = MotdBuilder::new();
+
+        assert_eq!(
+            builder.push(&reply("375", "- irc.example.com Message of the day -")),
+            None
+        );
+        assert_eq!(
+            builder.push(&reply("372", "- Never gonna give you up!")),
+            None
+        );
+        assert_eq!(builder.push(&reply("372", "-")), None);
+        assert_eq!(
+            builder.push(&reply("372", "- Never gonna let you down!")),
+            None
+        );
+        assert_eq!(
+            builder.push(&reply("376", "End of /MOTD command.")),
+            Some("Never gonna give you up!\n\nNever gonna let you down!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_motd_reuse() {
+        let mut builder = MotdBuilder::new();
+        builder.push(&reply("372", "- Old"));
+        builder.push(&reply("376", "End of /MOTD command."));
+
+        builder.push(&reply("375", "- irc.example.com Message of the day -"));
+        builder.push(&reply("372", "- New"));
+        builder.push(&Line::from(("PING", vec!["irc.example.com"])));
+
+        assert_eq!(
+            builder.push(&reply("376", "End of /MOTD command.")),
+            Some("New".to_string())
+        );
+    }
+}
@@ -0,0 +1,236 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{Line, Source};
+use std::collections::HashMap;
+
+/// A channel's visibility, as given by the symbol in an `RPL_NAMREPLY`
+/// (`353`) reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelVisibility {
+    /// A public channel (`=`).
+    Public,
+
+    /// A private channel (`*`).
+    Private,
+
+    /// A secret channel (`@`).
+    Secret,
+}
+
+impl ChannelVisibility {
+    /// Gets a channel visibility from its symbol.
+    ///
+    /// # Arguments
+    /// - `symbol` - The visibility symbol.
+    ///
+    /// # Returns
+    /// - [`Option<ChannelVisibility>`] - The channel visibility, or
+    ///   [`None`] if the symbol is not known.
+    ///
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "=" => Some(Self::Public),
+            "*" => Some(Self::Private),
+            "@" => Some(Self::Secret),
+            _ => None,
+        }
+    }
+}
+
+/// A struct representing a parsed `RPL_NAMREPLY` (`353`) reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Names {
+    /// The channel these names belong to.
+    pub channel: String,
+
+    /// The channel's visibility. This is optional, and will be [`None`]
+    /// if the server didn't send a visibility symbol.
+    pub visibility: Option<ChannelVisibility>,
+
+    /// The channel's members.
+    pub members: Vec<NamesEntry>,
+}
+
+/// A single member listed in a [`Names`] reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamesEntry {
+    /// The member's prefix symbols (such as `@` or `+`), in the order
+    /// given. This will be an empty vector if there are none.
+    pub prefixes: Vec<char>,
+
+    /// The member's source. With the `userhost-in-names` capability,
+    /// this will include the user and host; otherwise only the nick
+    /// will be present.
+    pub source: Source,
+}
+
+/// A builder which collects the `RPL_NAMREPLY` (`353`) replies for each
+/// channel, finishing a channel's list when its `RPL_ENDOFNAMES` (`366`)
+/// arrives. Replies for several channels can be interleaved.
+///
+/// # Example
+/// ```
+/// let msg = ":irc.example.com 353 me = #rickastley :@rick nick\r\n\
+///            :irc.example.com 353 me = #rickastley :+astley\r\n\
+///            :irc.example.com 366 me #rickastley :End of /NAMES list.\r\n";
+///
+/// let mut builder = ircparser::NamesBuilder::new("@+");
+/// let names = ircparser::parse(msg).unwrap().iter().find_map(|x| builder.push(x)).unwrap();
+///
+/// assert_eq!(names.channel, "#rickastley");
+/// assert_eq!(names.members.len(), 3);
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct NamesBuilder {
+    prefixes: String,
+    pending: HashMap<String, Names>,
+}
+
+impl NamesBuilder {
+    /// Creates a new [`NamesBuilder`].
+    ///
+    /// # Arguments
+    /// - `prefixes` - The membership prefix symbols the server uses, as
+    ///   per [`Line::names`].
+    ///
+    /// # Returns
+    /// - [`NamesBuilder`] - The new [`NamesBuilder`] instance.
+    ///
+    pub fn new(prefixes: &str) -> Self {
+        Self {
+            prefixes: prefixes.to_string(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Feeds a line to this builder. Lines other than `353` and `366`
+    /// replies are ignored.
+    ///
+    /// # Arguments
+    /// - `line` - The line to feed.
+    ///
+    /// # Returns
+    /// - [`Option<Names>`] - The channel's complete member list, if this
+    ///   line ended it. This will have no members if no `353` replies
+    ///   were fed for the channel.
+    ///
+    pub fn push(&mut self, line: &Line) -> Option<Names> {
+        if let Some(names) = line.names(&self.prefixes) {
+            match self.pending.get_mut(&names.channel) {
+                Some(pending) => {
+                    pending.visibility = pending.visibility.or(names.visibility);
+                    pending.members.extend(names.members);
+                }
+                None => {
+                    self.pending.insert(names.channel.clone(), names);
+                }
+            }
+            return None;
+        }
+
+        if line.command != "366" {
+            return None;
+        }
+
+        let channel = line.params.get(1)?;
+        Some(self.pending.remove(channel).unwrap_or_else(|| Names {
+            channel: channel.clone(),
+            visibility: None,
+            members: Vec::new(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test_names {
+    use super::{ChannelVisibility, NamesBuilder};
+    use crate::Line;
+
+    fn reply(command: &str, params: &[&str]) -> Line {
+        Line::from((command, params.to_vec()))
+    }
+
+    #[test]
+    fn test_names_builder() {
+        let mut builder = NamesBuilder::new("@+");
+
+        assert_eq!(
+            builder.push(&reply("353", &["me", "=", "#a", "@rick nick"])),
+            None
+        );
+        assert_eq!(
+            builder.push(&reply("353", &["me", "=", "#a", "+astley"])),
+            None
+        );
+        let names = builder
+            .push(&reply("366", &["me", "#a", "End of /NAMES list."]))
+            .unwrap();
+
+        assert_eq!(names.channel, "#a");
+        assert_eq!(names.visibility, Some(ChannelVisibility::Public));
+        let nicks: Vec<&str> = names
+            .members
+            .iter()
+            .map(|x| x.source.nick.as_str())
+            .collect();
+        assert_eq!(nicks, vec!["rick", "nick", "astley"]);
+        assert_eq!(names.members[2].prefixes, vec!['+']);
+    }
+
+    #[test]
+    fn test_names_builder_interleaved() {
+        let mut builder = NamesBuilder::new("@+");
+
+        builder.push(&reply("353", &["me", "=", "#a", "rick"]));
+        builder.push(&reply("353", &["me", "@", "#b", "astley"]));
+        let names = builder
+            .push(&reply("366", &["me", "#b", "End of /NAMES list."]))
+            .unwrap();
+        assert_eq!(names.members.len(), 1);
+        assert_eq!(names.members[0].source.nick, "astley");
+
+        let names = builder
+            .push(&reply("366", &["me", "#a", "End of /NAMES list."]))
+            .unwrap();
+        assert_eq!(names.members[0].source.nick, "rick");
+    }
+
+    #[test]
+    fn test_names_builder_empty() {
+        let mut builder = NamesBuilder::new("@+");
+        let names = builder
+            .push(&reply("366", &["me", "#a", "End of /NAMES list."]))
+            .unwrap();
+
+        assert!(names.members.is_empty());
+    }
+}
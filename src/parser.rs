@@ -0,0 +1,107 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{parse_with, Line, ParseConfig, ParseResult};
+use std::collections::VecDeque;
+
+/// A parser which holds its own [`ParseConfig`], for when the same
+/// configuration is used for every message.
+///
+/// # Example
+/// ```
+/// let parser = ircparser::Parser::new(ircparser::ParseConfig {
+///     normalize_command: true,
+///     ..Default::default()
+/// });
+///
+/// let line = parser.parse("privmsg #rickastley :Never gonna give you up!\r\n").unwrap().pop_front().unwrap();
+///
+/// assert_eq!(line.command, "PRIVMSG");
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct Parser {
+    /// The configuration this parser uses.
+    pub config: ParseConfig,
+}
+
+impl Parser {
+    /// Creates a new [`Parser`].
+    ///
+    /// # Arguments
+    /// - `config` - The [`ParseConfig`] to parse with.
+    ///
+    /// # Returns
+    /// - [`Parser`] - The new [`Parser`] instance.
+    ///
+    pub fn new(config: ParseConfig) -> Self {
+        Self { config }
+    }
+
+    /// Parses an IRC message using this parser's configuration, as per
+    /// [ircparser::parse_with](super::parse_with).
+    ///
+    /// # Arguments
+    /// - `text` - The text you want to parse.
+    ///
+    /// # Returns
+    /// - [`VecDeque<Line>`] - A [`VecDeque`] of all parsed [`Line`]s.
+    ///
+    pub fn parse(&self, text: &str) -> ParseResult<VecDeque<Line>> {
+        parse_with(text, &self.config)
+    }
+}
+
+#[cfg(test)]
+mod test_parser {
+    use super::Parser;
+    use crate::ParseConfig;
+
+    #[test]
+    fn test_parser_multiple_inputs() {
+        let parser = Parser::new(ParseConfig {
+            tolerant: true,
+            normalize_command: true,
+            ..Default::default()
+        });
+
+        let l1 = parser
+            .parse(":nick!user@host @id=123 privmsg #a :hi\r\n")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        let l2 = parser.parse("join #b\r\n").unwrap().pop_front().unwrap();
+
+        assert_eq!(&l1.tags["id"], "123");
+        assert_eq!(l1.command, "PRIVMSG");
+        assert_eq!(l2.command, "JOIN");
+        assert_eq!(l2.params, vec!["#b"]);
+    }
+}
@@ -0,0 +1,131 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// The CTCP low-level quote character.
+const M_QUOTE: char = '\x10';
+
+/// Applies CTCP low-level quoting to a message. NUL, CR, and LF
+/// characters, along with the quote character (`\x10`) itself, are
+/// replaced with the quote character followed by `0`, `r`, `n`, and
+/// `\x10` respectively.
+///
+/// # Arguments
+/// - `text` - The text to quote.
+///
+/// # Returns
+/// - [`String`] - The quoted text.
+///
+/// # Example
+/// ```
+/// assert_eq!(ircparser::low_level_quote("a\r\nb"), "a\x10r\x10nb");
+/// ```
+///
+pub fn low_level_quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\0' => quoted.push_str("\x100"),
+            '\r' => quoted.push_str("\x10r"),
+            '\n' => quoted.push_str("\x10n"),
+            M_QUOTE => quoted.push_str("\x10\x10"),
+            _ => quoted.push(c),
+        }
+    }
+
+    quoted
+}
+
+/// Removes CTCP low-level quoting from a message, reversing
+/// [`low_level_quote`]. Unknown quoted characters have the quote
+/// character dropped, and a trailing lone quote character is removed.
+///
+/// # Arguments
+/// - `text` - The text to dequote.
+///
+/// # Returns
+/// - [`String`] - The dequoted text.
+///
+/// # Example
+/// ```
+/// assert_eq!(ircparser::low_level_dequote("a\x10r\x10nb"), "a\r\nb");
+/// ```
+///
+pub fn low_level_dequote(text: &str) -> String {
+    let mut dequoted = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != M_QUOTE {
+            dequoted.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('0') => dequoted.push('\0'),
+            Some('r') => dequoted.push('\r'),
+            Some('n') => dequoted.push('\n'),
+            Some(x) => dequoted.push(x),
+            None => {}
+        }
+    }
+
+    dequoted
+}
+
+#[cfg(test)]
+mod test_quote {
+    use super::{low_level_dequote, low_level_quote};
+
+    #[test]
+    fn test_low_level_quote() {
+        assert_eq!(
+            low_level_quote("a\0b\rc\nd\x10e"),
+            "a\x100b\x10rc\x10nd\x10\x10e"
+        );
+        assert_eq!(low_level_quote("plain"), "plain");
+    }
+
+    #[test]
+    fn test_low_level_dequote() {
+        assert_eq!(
+            low_level_dequote("a\x100b\x10rc\x10nd\x10\x10e"),
+            "a\0b\rc\nd\x10e"
+        );
+        assert_eq!(low_level_dequote("a\x10xb\x10"), "axb");
+    }
+
+    #[test]
+    fn test_low_level_round_trip() {
+        for text in ["\0\r\n\x10", "\x10\x10n", "Never gonna\r\ngive you up!\0"] {
+            assert_eq!(low_level_dequote(&low_level_quote(text)), text);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// The severity of a [`StandardReply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// A `FAIL` reply, indicating a command failed.
+    Fail,
+
+    /// A `WARN` reply, indicating something went wrong, but not enough
+    /// to fail the command.
+    Warn,
+
+    /// A `NOTE` reply, providing information.
+    Note,
+}
+
+/// A struct representing an IRCv3 standard reply (`FAIL`, `WARN`, or
+/// `NOTE`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandardReply {
+    /// This reply's severity.
+    pub severity: Severity,
+
+    /// The command this reply relates to, or `*` if it doesn't relate
+    /// to a specific command.
+    pub command: String,
+
+    /// This reply's machine-readable code.
+    pub code: String,
+
+    /// Any context given for this reply. This will be an empty vector
+    /// if there is none.
+    pub context: Vec<String>,
+
+    /// This reply's human-readable description.
+    pub description: String,
+}
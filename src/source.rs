@@ -0,0 +1,196 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// A struct representing a decomposed source (`nick!user@host`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Source {
+    /// The source's nick. If the source is a server, this will be the
+    /// server's name.
+    pub nick: String,
+
+    /// The source's user. This is optional, and will be [`None`] if not
+    /// provided.
+    pub user: Option<String>,
+
+    /// The source's host. This is optional, and will be [`None`] if not
+    /// provided.
+    pub host: Option<String>,
+}
+
+impl Source {
+    /// Parses a source into its components. A leading colon, as found in
+    /// [`Line::source`](super::Line::source), is ignored.
+    ///
+    /// # Arguments
+    /// - `text` - The source to parse.
+    ///
+    /// # Returns
+    /// - [`Source`] - The decomposed source.
+    ///
+    /// # Example
+    /// ```
+    /// let source = ircparser::Source::parse(":nick!user@host.tmi.twitch.tv");
+    ///
+    /// assert_eq!(source.nick, "nick");
+    /// assert_eq!(source.user.unwrap(), "user");
+    /// assert_eq!(source.host.unwrap(), "host.tmi.twitch.tv");
+    /// ```
+    ///
+    pub fn parse(text: &str) -> Self {
+        let text = text.strip_prefix(':').unwrap_or(text);
+
+        let (rest, host) = match text.find('@') {
+            Some(idx) => (&text[..idx], Some(text[idx + 1..].to_string())),
+            None => (text, None),
+        };
+        let (nick, user) = match rest.find('!') {
+            Some(idx) => (&rest[..idx], Some(rest[idx + 1..].to_string())),
+            None => (rest, None),
+        };
+
+        Self {
+            nick: nick.to_string(),
+            user,
+            host,
+        }
+    }
+
+    /// Checks whether this source is a TS6 server ID (SID), such as
+    /// `42X`. SIDs are a digit followed by two uppercase letters or
+    /// digits.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this source is a SID.
+    ///
+    /// # Example
+    /// ```
+    /// assert!(ircparser::Source::parse(":42X").is_sid());
+    /// assert!(!ircparser::Source::parse(":nick").is_sid());
+    /// ```
+    ///
+    pub fn is_sid(&self) -> bool {
+        let bytes = self.nick.as_bytes();
+
+        self.user.is_none()
+            && self.host.is_none()
+            && bytes.len() == 3
+            && bytes[0].is_ascii_digit()
+            && bytes[1..]
+                .iter()
+                .all(|x| x.is_ascii_uppercase() || x.is_ascii_digit())
+    }
+
+    /// Checks whether this source is a TS6 user ID (UID), such as
+    /// `42XAAAAAB`. UIDs are the SID of the user's server followed by an
+    /// uppercase letter and five uppercase letters or digits.
+    ///
+    /// # Returns
+    /// - [`bool`] - Whether this source is a UID.
+    ///
+    /// # Example
+    /// ```
+    /// assert!(ircparser::Source::parse(":42XAAAAAB").is_uid());
+    /// assert!(!ircparser::Source::parse(":nick!user@host").is_uid());
+    /// ```
+    ///
+    pub fn is_uid(&self) -> bool {
+        let bytes = self.nick.as_bytes();
+
+        self.user.is_none()
+            && self.host.is_none()
+            && bytes.len() == 9
+            && bytes[0].is_ascii_digit()
+            && bytes[1..3]
+                .iter()
+                .all(|x| x.is_ascii_uppercase() || x.is_ascii_digit())
+            && bytes[3].is_ascii_uppercase()
+            && bytes[4..]
+                .iter()
+                .all(|x| x.is_ascii_uppercase() || x.is_ascii_digit())
+    }
+}
+
+#[cfg(test)]
+mod test_source {
+    use super::Source;
+
+    #[test]
+    fn test_parse_full() {
+        let source = Source::parse(":nick!user@host");
+
+        assert_eq!(source.nick, "nick");
+        assert_eq!(source.user, Some(String::from("user")));
+        assert_eq!(source.host, Some(String::from("host")));
+    }
+
+    #[test]
+    fn test_parse_nick_only() {
+        let source = Source::parse("nick");
+
+        assert_eq!(source.nick, "nick");
+        assert_eq!(source.user, None);
+        assert_eq!(source.host, None);
+    }
+
+    #[test]
+    fn test_parse_nick_host() {
+        let source = Source::parse("nick@host");
+
+        assert_eq!(source.nick, "nick");
+        assert_eq!(source.user, None);
+        assert_eq!(source.host, Some(String::from("host")));
+    }
+
+    #[test]
+    fn test_is_uid() {
+        let source = Source::parse(":42XAAAAAB");
+
+        assert!(source.is_uid());
+        assert!(!source.is_sid());
+    }
+
+    #[test]
+    fn test_is_sid() {
+        let source = Source::parse(":42X");
+
+        assert!(source.is_sid());
+        assert!(!source.is_uid());
+    }
+
+    #[test]
+    fn test_nick_is_not_uid() {
+        for text in [":nick", ":nick!user@host", ":rickastle", ":42xaaaaab"] {
+            let source = Source::parse(text);
+
+            assert!(!source.is_uid());
+            assert!(!source.is_sid());
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::ops::Range;
+
+/// A struct holding the byte ranges of each component of a line, as
+/// returned by [ircparser::parse_spans](super::parse_spans).
+///
+/// Each range covers exactly the text that would be stored in the
+/// corresponding field of a [`Line`](super::Line), so the tags range
+/// excludes the leading `@`, the source range includes the leading `:`,
+/// and the range of a trailing param excludes its leading `:`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineSpans {
+    /// The range of this line's tags. This is optional, and will be
+    /// [`None`] if there are none.
+    pub tags: Option<Range<usize>>,
+
+    /// The range of this line's source. This is optional, and will be
+    /// [`None`] if not provided.
+    pub source: Option<Range<usize>>,
+
+    /// The range of this line's command.
+    pub command: Range<usize>,
+
+    /// The ranges of each of this line's params.
+    pub params: Vec<Range<usize>>,
+}
+
+impl LineSpans {
+    /// Shifts every range in this struct forward by `offset` bytes.
+    pub(crate) fn offset(mut self, offset: usize) -> Self {
+        let shift = |r: Range<usize>| r.start + offset..r.end + offset;
+
+        self.tags = self.tags.map(shift);
+        self.source = self.source.map(shift);
+        self.command = shift(self.command);
+        self.params = self.params.into_iter().map(shift).collect();
+        self
+    }
+}
@@ -0,0 +1,364 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{parse_line, Line, ParseConfig, ParseError, ParseErrorKind, ParseResult};
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::AsyncBufRead;
+
+/// A [`Stream`] of [`Line`]s parsed from an [`AsyncBufRead`] reader.
+///
+/// Lines are yielded as soon as their `\r\n` terminator has been read,
+/// so a line split across several reads is parsed once it is complete.
+/// Any unterminated text left when the reader reaches EOF is parsed as
+/// a final line.
+///
+/// By default, there is no limit on how long a line can be, so a peer
+/// which never sends a terminator can make the parser buffer without
+/// bound. Use [`AsyncStreamParser::with_max_line_len`] to set a limit.
+///
+/// If the reader fails, an error of kind
+/// [`Io`](crate::ParseErrorKind::Io) is yielded, and the stream then
+/// ends. Any partial line read before the failure is dropped.
+///
+/// This is only available with the `tokio` feature.
+///
+/// # Example
+/// ```
+/// use futures_core::Stream;
+/// use std::future::poll_fn;
+/// use std::pin::Pin;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let reader: &[u8] = b"PRIVMSG #rickastley :Never gonna give you up!\r\n";
+/// let mut parser = ircparser::AsyncStreamParser::new(reader);
+///
+/// let line = poll_fn(|cx| Pin::new(&mut parser).poll_next(cx)).await.unwrap().unwrap();
+///
+/// assert_eq!(line.command, "PRIVMSG");
+/// # }
+/// ```
+///
+#[derive(Debug)]
+pub struct AsyncStreamParser<R> {
+    reader: R,
+    config: ParseConfig,
+    buffer: Vec<u8>,
+    scanned: usize,
+    max_line_len: Option<usize>,
+    discarding: bool,
+    done: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncStreamParser<R> {
+    /// Creates a new [`AsyncStreamParser`] using the default
+    /// configuration.
+    ///
+    /// # Arguments
+    /// - `reader` - The reader to parse lines from.
+    ///
+    /// # Returns
+    /// - [`AsyncStreamParser`] - The new [`AsyncStreamParser`] instance.
+    ///
+    pub fn new(reader: R) -> Self {
+        Self::with_config(reader, ParseConfig::default())
+    }
+
+    /// Creates a new [`AsyncStreamParser`] using the given
+    /// configuration.
+    ///
+    /// # Arguments
+    /// - `reader` - The reader to parse lines from.
+    /// - `config` - The [`ParseConfig`] to parse with.
+    ///
+    /// # Returns
+    /// - [`AsyncStreamParser`] - The new [`AsyncStreamParser`] instance.
+    ///
+    pub fn with_config(reader: R, config: ParseConfig) -> Self {
+        Self {
+            reader,
+            config,
+            buffer: Vec::new(),
+            scanned: 0,
+            max_line_len: None,
+            discarding: false,
+            done: false,
+        }
+    }
+
+    /// Sets the maximum length of a line in bytes, excluding its `\r\n`
+    /// terminator. When a line exceeds this, a [`ParseError`] is yielded
+    /// and the rest of the line is discarded up to the next terminator,
+    /// after which parsing resumes as normal.
+    ///
+    /// # Arguments
+    /// - `max_line_len` - The maximum length of a line in bytes.
+    ///
+    /// # Returns
+    /// - [`AsyncStreamParser`] - This [`AsyncStreamParser`] instance.
+    ///
+    pub fn with_max_line_len(mut self, max_line_len: usize) -> Self {
+        self.max_line_len = Some(max_line_len);
+        self
+    }
+
+    fn too_long(&self, len: usize) -> bool {
+        self.max_line_len.is_some_and(|max| len > max)
+    }
+
+    fn too_long_error(&self) -> ParseError {
+        let max = self.max_line_len.unwrap_or_default();
+        ParseError::with_kind(
+            ParseErrorKind::TooLong { max },
+            &format!("line is too long (maximum is {max} bytes)"),
+        )
+    }
+
+    fn parse_bytes(&self, bytes: Vec<u8>) -> ParseResult<Line> {
+        match String::from_utf8(bytes) {
+            Ok(line) => parse_line(&line, &self.config),
+            Err(e) => Err(ParseError::with_kind(
+                ParseErrorKind::InvalidUtf8,
+                &e.to_string(),
+            )),
+        }
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for AsyncStreamParser<R> {
+    type Item = ParseResult<Line>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            // Everything before `scanned` is known not to start a
+            // terminator, so each byte is only searched once.
+            let found = this.buffer[this.scanned..]
+                .windows(2)
+                .position(|x| x == b"\r\n")
+                .map(|x| this.scanned + x);
+            this.scanned = this.buffer.len().saturating_sub(1);
+
+            if let Some(idx) = found {
+                this.scanned = 0;
+                let mut line: Vec<u8> = this.buffer.drain(..idx + 2).collect();
+                line.truncate(idx);
+
+                if this.discarding {
+                    // This is the end of an overlong line.
+                    this.discarding = false;
+                    continue;
+                }
+                if line.is_empty() {
+                    continue;
+                }
+                if this.too_long(line.len()) {
+                    return Poll::Ready(Some(Err(this.too_long_error())));
+                }
+                return Poll::Ready(Some(this.parse_bytes(line)));
+            }
+
+            // A trailing \r may be the start of a terminator split across
+            // reads, so it doesn't count towards the line's length.
+            let len = this.buffer.len() - this.buffer.ends_with(b"\r") as usize;
+            if this.discarding || this.too_long(len) {
+                this.buffer.drain(..len);
+                this.scanned = 0;
+
+                if !this.discarding {
+                    this.discarding = true;
+                    return Poll::Ready(Some(Err(this.too_long_error())));
+                }
+            }
+
+            if this.done {
+                if this.buffer.is_empty() || this.discarding {
+                    return Poll::Ready(None);
+                }
+                this.scanned = 0;
+                let line = std::mem::take(&mut this.buffer);
+                return Poll::Ready(Some(this.parse_bytes(line)));
+            }
+
+            let data = match Pin::new(&mut this.reader).poll_fill_buf(cx) {
+                Poll::Ready(Ok(x)) => x,
+                Poll::Ready(Err(e)) => {
+                    // Stop here rather than polling a failing reader again.
+                    this.done = true;
+                    this.discarding = true;
+                    this.buffer.clear();
+                    this.scanned = 0;
+                    return Poll::Ready(Some(Err(ParseError::with_kind(
+                        ParseErrorKind::Io { kind: e.kind() },
+                        &e.to_string(),
+                    ))));
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+
+            let len = data.len();
+            if len == 0 {
+                this.done = true;
+            } else {
+                this.buffer.extend_from_slice(data);
+                Pin::new(&mut this.reader).consume(len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_stream {
+    use super::AsyncStreamParser;
+    use crate::{Line, ParseErrorKind, ParseResult};
+    use futures_core::Stream;
+    use std::future::poll_fn;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, BufReader, ReadBuf};
+
+    /// A reader which always fails.
+    struct Failing;
+
+    impl AsyncRead for Failing {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            _: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken")))
+        }
+    }
+
+    async fn next<R: AsyncBufRead + Unpin>(
+        parser: &mut AsyncStreamParser<R>,
+    ) -> Option<ParseResult<Line>> {
+        poll_fn(|cx| Pin::new(&mut *parser).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn test_stream_lines() {
+        let msg = "@id=123 PRIVMSG #rickastley :Never gonna give you up!\r\n@id=456 PRIVMSG #rickastley :Never gonna let you down!\r\n";
+
+        // A tiny buffer forces lines to be split across several reads.
+        let mut parser = AsyncStreamParser::new(BufReader::with_capacity(4, msg.as_bytes()));
+
+        let l1 = next(&mut parser).await.unwrap().unwrap();
+        let l2 = next(&mut parser).await.unwrap().unwrap();
+
+        assert_eq!(&l1.tags["id"], "123");
+        assert_eq!(l1.params[1], "Never gonna give you up!");
+        assert_eq!(&l2.tags["id"], "456");
+        assert_eq!(l2.params[1], "Never gonna let you down!");
+        assert!(next(&mut parser).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_unterminated() {
+        let reader: &[u8] = b"PRIVMSG #rickastley :Never gonna give you up!";
+        let mut parser = AsyncStreamParser::new(reader);
+
+        let line = next(&mut parser).await.unwrap().unwrap();
+
+        assert_eq!(line.params[1], "Never gonna give you up!");
+        assert!(next(&mut parser).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_max_line_len() {
+        let msg = format!(
+            "PRIVMSG #rickastley :{}\r\nPRIVMSG #rickastley :Never gonna give you up!\r\n",
+            "a".repeat(100)
+        );
+        let reader = BufReader::with_capacity(8, msg.as_bytes());
+        let mut parser = AsyncStreamParser::new(reader).with_max_line_len(64);
+
+        let e = next(&mut parser).await.unwrap().unwrap_err();
+        assert_eq!(e.kind(), &ParseErrorKind::TooLong { max: 64 });
+
+        let line = next(&mut parser).await.unwrap().unwrap();
+        assert_eq!(line.params[1], "Never gonna give you up!");
+        assert!(next(&mut parser).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_max_line_len_unterminated() {
+        let msg = "a".repeat(10_000);
+        let reader = BufReader::with_capacity(16, msg.as_bytes());
+        let mut parser = AsyncStreamParser::new(reader).with_max_line_len(64);
+
+        assert!(next(&mut parser).await.unwrap().is_err());
+        assert!(parser.buffer.len() <= 64);
+        assert!(next(&mut parser).await.is_none());
+        assert!(parser.buffer.len() <= 64);
+    }
+
+    #[tokio::test]
+    async fn test_stream_byte_at_a_time() {
+        let msg = "PING :a\r\nPRIVMSG #rickastley :Never gonna give you up!\r\n";
+        let mut parser = AsyncStreamParser::new(BufReader::with_capacity(1, msg.as_bytes()));
+
+        assert_eq!(next(&mut parser).await.unwrap().unwrap().params, vec!["a"]);
+        let line = next(&mut parser).await.unwrap().unwrap();
+        assert_eq!(line.params[1], "Never gonna give you up!");
+        assert!(next(&mut parser).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_invalid_utf8() {
+        let reader: &[u8] = b"PING :\xff\r\nPING :a\r\n";
+        let mut parser = AsyncStreamParser::new(reader);
+
+        let e = next(&mut parser).await.unwrap().unwrap_err();
+        assert_eq!(e.kind(), &ParseErrorKind::InvalidUtf8);
+        assert_eq!(next(&mut parser).await.unwrap().unwrap().params, vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_io_error() {
+        let reader = BufReader::new(b"PING :a\r\nPING :b".chain(Failing));
+        let mut parser = AsyncStreamParser::new(reader);
+
+        assert_eq!(next(&mut parser).await.unwrap().unwrap().params, vec!["a"]);
+
+        let e = next(&mut parser).await.unwrap().unwrap_err();
+        assert_eq!(
+            e.kind(),
+            &ParseErrorKind::Io {
+                kind: io::ErrorKind::BrokenPipe
+            }
+        );
+        assert!(next(&mut parser).await.is_none());
+    }
+}
@@ -0,0 +1,328 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::HashMap;
+
+/// Helper methods for working with a line's tags.
+///
+/// This is implemented for the [`HashMap`] used by
+/// [`Line::tags`](super::Line::tags).
+pub trait Tags {
+    /// Gets a tag's value split into a list of values. Empty values are
+    /// left out.
+    ///
+    /// # Arguments
+    /// - `key` - The tag's key.
+    /// - `sep` - The separator between values.
+    ///
+    /// # Returns
+    /// - [`Vec<&str>`] - The tag's values. This will be empty if the tag
+    ///   is not present.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::Tags;
+    ///
+    /// let msg = "@badges=subscriber/12,premium/1 PRIVMSG #rickastley :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.tags.get_list("badges", ','), vec!["subscriber/12", "premium/1"]);
+    /// ```
+    ///
+    fn get_list(&self, key: &str, sep: char) -> Vec<&str>;
+
+    /// Gets a tag's value decoded into another type, using its
+    /// [`TagDecode`] implementation.
+    ///
+    /// # Arguments
+    /// - `key` - The tag's key.
+    ///
+    /// # Returns
+    /// - [`Option<T>`] - The decoded value, or [`None`] if the tag is not
+    ///   present or could not be decoded.
+    ///
+    /// # Example
+    /// ```
+    /// use ircparser::Tags;
+    ///
+    /// let msg = "@tmi-sent-ts=1507246572675 PRIVMSG #rickastley :Never gonna give you up!\r\n";
+    /// let line = ircparser::parse(msg).unwrap().pop_front().unwrap();
+    ///
+    /// assert_eq!(line.tags.get_as::<u64>("tmi-sent-ts"), Some(1507246572675));
+    /// ```
+    ///
+    fn get_as<T: TagDecode>(&self, key: &str) -> Option<T>;
+}
+
+/// A type which can be decoded from a tag's value, for use with
+/// [`Tags::get_as`].
+///
+/// This is implemented for [`String`] and the integer types, and can be
+/// implemented for your own types to decode custom tag formats.
+pub trait TagDecode: Sized {
+    /// Decodes a tag's (unescaped) value.
+    ///
+    /// # Arguments
+    /// - `raw` - The tag's value.
+    ///
+    /// # Returns
+    /// - [`Option<Self>`] - The decoded value, or [`None`] if it could
+    ///   not be decoded.
+    ///
+    fn decode(raw: &str) -> Option<Self>;
+}
+
+impl TagDecode for String {
+    fn decode(raw: &str) -> Option<Self> {
+        Some(raw.to_string())
+    }
+}
+
+macro_rules! impl_tag_decode {
+    ($($t:ty),*) => {
+        $(
+            impl TagDecode for $t {
+                fn decode(raw: &str) -> Option<Self> {
+                    raw.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_tag_decode!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Tags for HashMap<String, String> {
+    fn get_list(&self, key: &str, sep: char) -> Vec<&str> {
+        match self.get(key) {
+            Some(x) => x.split(sep).filter(|x| !x.is_empty()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn get_as<T: TagDecode>(&self, key: &str) -> Option<T> {
+        T::decode(self.get(key)?)
+    }
+}
+
+/// Unescapes a tag value, as per the IRCv3 message tags specification.
+/// Unknown escape sequences drop the backslash, and a trailing lone
+/// backslash is removed.
+pub(crate) fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(':') => unescaped.push(';'),
+            Some('s') => unescaped.push(' '),
+            Some('r') => unescaped.push('\r'),
+            Some('n') => unescaped.push('\n'),
+            Some(x) => unescaped.push(x),
+            None => {}
+        }
+    }
+
+    unescaped
+}
+
+/// Finds the first unknown escape sequence in a raw tag value, if any,
+/// along with its byte offset. A trailing lone backslash is also
+/// reported.
+pub(crate) fn unknown_escape(value: &str) -> Option<(usize, &str)> {
+    let mut chars = value.char_indices();
+
+    while let Some((idx, c)) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+
+        match chars.next() {
+            Some((_, ':' | 's' | 'r' | 'n' | '\\')) => {}
+            Some((next, x)) => return Some((idx, &value[idx..next + x.len_utf8()])),
+            None => return Some((idx, &value[idx..])),
+        }
+    }
+
+    None
+}
+
+/// Checks whether a tag key is valid, as per the IRCv3 message tags
+/// specification.
+pub(crate) fn is_valid_key(key: &str) -> bool {
+    let key = key.strip_prefix('+').unwrap_or(key);
+    let (vendor, name) = match key.rfind('/') {
+        Some(idx) => (Some(&key[..idx]), &key[idx + 1..]),
+        None => (None, key),
+    };
+
+    let vendor_ok = vendor.map_or(true, |x| {
+        !x.is_empty()
+            && x.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'))
+    });
+    let name_ok = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+
+    vendor_ok && name_ok
+}
+
+/// Escapes a tag value, as per the IRCv3 message tags specification.
+pub(crate) fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            ';' => escaped.push_str("\\:"),
+            ' ' => escaped.push_str("\\s"),
+            '\\' => escaped.push_str("\\\\"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            x => escaped.push(x),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod test_tags {
+    use super::{escape_value, is_valid_key, unescape_value, unknown_escape, TagDecode, Tags};
+    use collection_macros::hashmap;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_unescape_value() {
+        assert_eq!(unescape_value(r"a\:b\sc\\d\re\nf"), "a;b c\\d\re\nf");
+        assert_eq!(unescape_value(r"\b"), "b");
+        assert_eq!(unescape_value("a\\"), "a");
+    }
+
+    #[test]
+    fn test_escape_value() {
+        assert_eq!(escape_value("a;b c\\d\re\nf"), r"a\:b\sc\\d\re\nf");
+    }
+
+    #[test]
+    fn test_get_list_comma() {
+        let tags: HashMap<String, String> = hashmap! {
+            String::from("badges") => String::from("subscriber/12,,premium/1,"),
+        };
+
+        assert_eq!(
+            tags.get_list("badges", ','),
+            vec!["subscriber/12", "premium/1"]
+        );
+        assert!(tags.get_list("emotes", ',').is_empty());
+    }
+
+    #[test]
+    fn test_get_list_slash() {
+        let tags: HashMap<String, String> = hashmap! {
+            String::from("emotes") => String::from("25:0-4/1902:6-10"),
+        };
+
+        assert_eq!(tags.get_list("emotes", '/'), vec!["25:0-4", "1902:6-10"]);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Colour(u8, u8, u8);
+
+    impl TagDecode for Colour {
+        fn decode(raw: &str) -> Option<Self> {
+            let hex = raw.strip_prefix('#')?;
+            if hex.len() != 6 {
+                return None;
+            }
+
+            let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+            Some(Colour(channel(0)?, channel(2)?, channel(4)?))
+        }
+    }
+
+    #[test]
+    fn test_get_as_custom() {
+        let tags: HashMap<String, String> = hashmap! {
+            String::from("color") => String::from("#FF8000"),
+            String::from("bad-color") => String::from("orange"),
+        };
+
+        assert_eq!(tags.get_as::<Colour>("color"), Some(Colour(255, 128, 0)));
+        assert_eq!(tags.get_as::<Colour>("bad-color"), None);
+        assert_eq!(tags.get_as::<Colour>("missing"), None);
+    }
+
+    #[test]
+    fn test_get_as_builtin() {
+        let tags: HashMap<String, String> = hashmap! {
+            String::from("room-id") => String::from("1337"),
+        };
+
+        assert_eq!(tags.get_as::<u32>("room-id"), Some(1337));
+        assert_eq!(tags.get_as::<u8>("room-id"), None);
+        assert_eq!(tags.get_as::<String>("room-id"), Some(String::from("1337")));
+    }
+
+    #[test]
+    fn test_unknown_escape() {
+        assert_eq!(unknown_escape(r"a\:b\sc\\d\re\nf"), None);
+        assert_eq!(unknown_escape(r"a\bc\x"), Some((1, r"\b")));
+        assert_eq!(unknown_escape("a\\"), Some((1, "\\")));
+    }
+
+    #[test]
+    fn test_is_valid_key() {
+        for key in [
+            "id",
+            "+typing",
+            "example.com/foo-bar",
+            "+draft/react",
+            "tmi-sent-ts",
+        ] {
+            assert!(is_valid_key(key));
+        }
+
+        for key in [
+            "",
+            "+",
+            "foo_bar",
+            "a b",
+            "/foo",
+            "example.com/",
+            "foo/bar/baz",
+        ] {
+            assert!(!is_valid_key(key));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Gets the number of days between the unix epoch and a date in the
+/// proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Parses a UTC timestamp in the format used by the IRCv3 `time` tag
+/// (`YYYY-MM-DDThh:mm:ss.sssZ`). The fractional seconds are optional.
+pub(crate) fn parse_iso8601(text: &str) -> Option<SystemTime> {
+    let text = text.strip_suffix('Z')?;
+    let (date, time) = text.split_once('T')?;
+
+    let date: Vec<&str> = date.split('-').collect();
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (time, None),
+    };
+    let time: Vec<&str> = time.split(':').collect();
+    if date.len() != 3 || time.len() != 3 {
+        return None;
+    }
+
+    let field = |x: &str, max: i64| -> Option<i64> {
+        let value: i64 = x.parse().ok()?;
+        if x.bytes().all(|b| b.is_ascii_digit()) && value <= max {
+            Some(value)
+        } else {
+            None
+        }
+    };
+    let (year, month, day) = (
+        field(date[0], 9999)?,
+        field(date[1], 12)?,
+        field(date[2], 31)?,
+    );
+    let (hour, minute, second) = (
+        field(time[0], 23)?,
+        field(time[1], 59)?,
+        field(time[2], 60)?,
+    );
+    if month == 0 || day == 0 {
+        return None;
+    }
+
+    let nanos = match fraction {
+        Some(x) if !x.is_empty() && x.len() <= 9 && x.bytes().all(|b| b.is_ascii_digit()) => {
+            x.parse::<u32>().ok()? * 10u32.pow(9 - x.len() as u32)
+        }
+        Some(_) => return None,
+        None => 0,
+    };
+
+    let secs = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+    let secs = u64::try_from(secs).ok()?;
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+#[cfg(test)]
+mod test_time {
+    use super::parse_iso8601;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_parse_iso8601() {
+        assert_eq!(parse_iso8601("1970-01-01T00:00:00Z"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_iso8601("2021-01-01T00:00:00.000Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(1609459200))
+        );
+        assert_eq!(
+            parse_iso8601("2011-10-19T16:40:51.620Z"),
+            Some(UNIX_EPOCH + Duration::from_millis(1319042451620))
+        );
+    }
+
+    #[test]
+    fn test_parse_iso8601_invalid() {
+        for text in [
+            "",
+            "2021-01-01T00:00:00",
+            "2021-01-01 00:00:00Z",
+            "2021-13-01T00:00:00Z",
+            "2021-01-01T00:00:00.Z",
+            "1969-12-31T23:59:59Z",
+            "+021-01-01T00:00:00Z",
+        ] {
+            assert_eq!(parse_iso8601(text), None, "{text:?} should not parse");
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// Parses the value of a Twitch `emotes` tag, such as
+/// `25:0-4,12-16/1902:6-10`.
+///
+/// # Arguments
+/// - `value` - The tag's value.
+///
+/// # Returns
+/// - [`Vec<(String, Vec<(usize, usize)>)>`] - Each emote's ID, paired
+///   with the inclusive character ranges (start and end) it covers in
+///   the message, in the order given. Malformed ranges are skipped, and
+///   this will be empty if the value is empty.
+///
+/// # Example
+/// ```
+/// let emotes = ircparser::parse_twitch_emotes("25:0-4,12-16/1902:6-10");
+///
+/// assert_eq!(
+///     emotes,
+///     vec![
+///         ("25".to_string(), vec![(0, 4), (12, 16)]),
+///         ("1902".to_string(), vec![(6, 10)]),
+///     ]
+/// );
+/// ```
+///
+pub fn parse_twitch_emotes(value: &str) -> Vec<(String, Vec<(usize, usize)>)> {
+    value
+        .split('/')
+        .filter_map(|x| x.split_once(':'))
+        .filter(|(id, _)| !id.is_empty())
+        .map(|(id, ranges)| {
+            let ranges = ranges
+                .split(',')
+                .filter_map(|x| {
+                    let (start, end) = x.split_once('-')?;
+                    Some((start.parse().ok()?, end.parse().ok()?))
+                })
+                .collect();
+            (id.to_string(), ranges)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_twitch {
+    use super::parse_twitch_emotes;
+
+    #[test]
+    fn test_parse_twitch_emotes() {
+        assert_eq!(
+            parse_twitch_emotes("25:0-4,12-16/1902:6-10/emotesv2_abc:18-22"),
+            vec![
+                ("25".to_string(), vec![(0, 4), (12, 16)]),
+                ("1902".to_string(), vec![(6, 10)]),
+                ("emotesv2_abc".to_string(), vec![(18, 22)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_twitch_emotes_empty() {
+        assert!(parse_twitch_emotes("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_twitch_emotes_malformed() {
+        assert_eq!(
+            parse_twitch_emotes("25:0-4,x-2,3/nonsense/:1-2"),
+            vec![("25".to_string(), vec![(0, 4)])]
+        );
+    }
+}
@@ -0,0 +1,390 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2022-present, Ethan Henderson
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{line_spans, tags, ParseConfig, MAX_TAGS_LEN};
+use std::ops::Range;
+
+/// The maximum length of a line in bytes, excluding its tags but
+/// including the trailing `\r\n`.
+const MAX_LINE_LEN: usize = 512;
+
+/// The severity of a [`ValidationIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSeverity {
+    /// The message is invalid, and may be rejected or misread.
+    Error,
+
+    /// The message is technically valid, but likely to be a mistake.
+    Warning,
+}
+
+/// A problem found in a message by [ircparser::validate](super::validate).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// This issue's severity.
+    pub severity: IssueSeverity,
+
+    /// The byte range of the offending text within the whole message.
+    pub range: Range<usize>,
+
+    /// A description of this issue.
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn new(severity: IssueSeverity, range: Range<usize>, message: &str) -> Self {
+        Self {
+            severity,
+            range,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Checks a message for structural problems, reporting every issue
+/// found rather than stopping at the first. This checks for:
+/// - lines with no command;
+/// - tags after the source, or no space before the trailing param;
+/// - invalid tag keys and unknown tag escape sequences;
+/// - tag sections longer than 8191 bytes, and lines longer than 512
+///   bytes (excluding tags);
+/// - NUL, and CR or LF characters outside of line terminators;
+/// - a final line without a `\r\n` terminator.
+///
+/// # Arguments
+/// - `text` - The message to check. This can comprise of multiple lines,
+///   as with [`parse`](super::parse).
+///
+/// # Returns
+/// - [`Vec<ValidationIssue>`] - Every issue found, in the order they
+///   appear. This will be empty if the message is valid.
+///
+/// # Example
+/// ```
+/// assert!(ircparser::validate("PRIVMSG #rickastley :Never gonna give you up!\r\n").is_empty());
+///
+/// let issues = ircparser::validate("@a=\\x PRIVMSG:hi\r\n");
+/// assert_eq!(issues.len(), 2);
+/// assert_eq!(issues[0].range, 3..5);
+/// ```
+///
+pub fn validate(text: &str) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let config = ParseConfig {
+        tolerant: true,
+        ..Default::default()
+    };
+    let mut offset = 0;
+
+    for line in text.split("\r\n") {
+        let start = offset;
+        offset += line.len() + 2;
+        if line.is_empty() {
+            continue;
+        }
+
+        let shift = |r: Range<usize>| r.start + start..r.end + start;
+
+        for (idx, c) in line.char_indices() {
+            if matches!(c, '\0' | '\r' | '\n') {
+                issues.push(ValidationIssue::new(
+                    IssueSeverity::Error,
+                    shift(idx..idx + 1),
+                    &format!("illegal character {c:?}"),
+                ));
+            }
+        }
+
+        let spans = match line_spans(line, &config) {
+            Ok(spans) => spans,
+            Err(e) => {
+                issues.push(ValidationIssue::new(
+                    IssueSeverity::Error,
+                    shift(0..line.len()),
+                    &e.details,
+                ));
+                continue;
+            }
+        };
+
+        let body_start = match &spans.tags {
+            Some(r) => {
+                check_tags(&line[r.clone()], r.start + start, &mut issues);
+
+                if r.end + 1 > MAX_TAGS_LEN {
+                    issues.push(ValidationIssue::new(
+                        IssueSeverity::Error,
+                        shift(0..r.end),
+                        &format!("tag section is too long (maximum is {MAX_TAGS_LEN} bytes)"),
+                    ));
+                }
+
+                if let Some(source) = &spans.source {
+                    if source.start < r.start {
+                        issues.push(ValidationIssue::new(
+                            IssueSeverity::Error,
+                            shift(r.start - 1..r.end),
+                            "tags appear after the source",
+                        ));
+                    }
+                }
+
+                r.end + 1
+            }
+            None => 0,
+        };
+
+        if line.len() - body_start + 2 > MAX_LINE_LEN {
+            issues.push(ValidationIssue::new(
+                IssueSeverity::Error,
+                shift(body_start..line.len()),
+                &format!("line is too long (maximum is {MAX_LINE_LEN} bytes, excluding tags)"),
+            ));
+        }
+
+        if line[spans.command.end..].starts_with(':') {
+            issues.push(ValidationIssue::new(
+                IssueSeverity::Error,
+                shift(spans.command.end..spans.command.end + 1),
+                "no space before the trailing param",
+            ));
+        }
+    }
+
+    if !text.is_empty() && !text.ends_with("\r\n") {
+        let start = text.rfind("\r\n").map_or(0, |x| x + 2);
+        issues.push(ValidationIssue::new(
+            IssueSeverity::Warning,
+            start..text.len(),
+            "line is not terminated with \\r\\n",
+        ));
+    }
+
+    issues.sort_by_key(|x| x.range.start);
+    issues
+}
+
+/// Checks each tag in a tag section, which starts at `offset`.
+fn check_tags(section: &str, offset: usize, issues: &mut Vec<ValidationIssue>) {
+    let mut start = offset;
+
+    for part in section.split(';') {
+        let part_start = start;
+        start += part.len() + 1;
+        if part.is_empty() {
+            continue;
+        }
+
+        let key = part.split_once('=').map_or(part, |(k, _)| k);
+        if !tags::is_valid_key(key) {
+            issues.push(ValidationIssue::new(
+                IssueSeverity::Error,
+                part_start..part_start + key.len(),
+                &format!("invalid tag key {key:?}"),
+            ));
+        }
+
+        let value_start = part_start + key.len() + 1;
+        let mut value = part.get(key.len() + 1..).unwrap_or("");
+        let mut value_offset = value_start;
+        while let Some((idx, seq)) = tags::unknown_escape(value) {
+            let range = value_offset + idx..value_offset + idx + seq.len();
+            issues.push(ValidationIssue::new(
+                IssueSeverity::Warning,
+                range.clone(),
+                &format!("unknown tag escape sequence {seq:?}"),
+            ));
+            value = &value[idx + seq.len()..];
+            value_offset = range.end;
+        }
+    }
+}
+
+/// Checks whether a channel name is valid.
+///
+/// # Arguments
+/// - `name` - The channel name to check.
+/// - `chantypes` - The characters channel names may start with (the
+///   `CHANTYPES` ISUPPORT token, usually `"#&"`).
+/// - `channellen` - The maximum length of a channel name in bytes (the
+///   `CHANNELLEN` ISUPPORT token), including its prefix.
+///
+/// # Returns
+/// - [`bool`] - Whether the channel name is valid.
+///
+/// # Example
+/// ```
+/// assert!(ircparser::is_valid_channel("#rickastley", "#&", 50));
+/// assert!(!ircparser::is_valid_channel("#rick astley", "#&", 50));
+/// ```
+///
+pub fn is_valid_channel(name: &str, chantypes: &str, channellen: usize) -> bool {
+    name.len() > 1
+        && name.len() <= channellen
+        && name.starts_with(|c| chantypes.contains(c))
+        && !name.contains([' ', ',', '\x07', '\0', '\r', '\n'])
+}
+
+/// Checks whether a nick is valid, as per RFC2812. Nicks are made up
+/// of letters, digits, `-`, and the special characters ``[]\`_^{|}``,
+/// and may not start with a digit or `-`.
+///
+/// # Arguments
+/// - `nick` - The nick to check.
+/// - `nicklen` - The maximum length of a nick in bytes (the `NICKLEN`
+///   ISUPPORT token).
+///
+/// # Returns
+/// - [`bool`] - Whether the nick is valid.
+///
+/// # Example
+/// ```
+/// assert!(ircparser::is_valid_nick("rick", 30));
+/// assert!(!ircparser::is_valid_nick("1rick", 30));
+/// ```
+///
+pub fn is_valid_nick(nick: &str, nicklen: usize) -> bool {
+    let is_special = |c: char| "[]\\`_^{|}".contains(c);
+
+    nick.len() <= nicklen
+        && nick.starts_with(|c: char| c.is_ascii_alphabetic() || is_special(c))
+        && nick
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || is_special(c))
+}
+
+#[cfg(test)]
+mod test_validate {
+    use super::{is_valid_channel, is_valid_nick, validate, IssueSeverity};
+
+    #[test]
+    fn test_valid_channel() {
+        assert!(is_valid_channel("#chan", "#&", 50));
+        assert!(is_valid_channel("&chan", "#&", 50));
+    }
+
+    #[test]
+    fn test_channel_too_long() {
+        assert!(is_valid_channel("#chan", "#&", 5));
+        assert!(!is_valid_channel("#chann", "#&", 5));
+    }
+
+    #[test]
+    fn test_channel_bad_prefix() {
+        assert!(!is_valid_channel("chan", "#&", 50));
+        assert!(!is_valid_channel("#", "#&", 50));
+    }
+
+    #[test]
+    fn test_channel_illegal_chars() {
+        for name in ["#a b", "#a,b", "#a\x07b", "#a\0b", "#a\rb", "#a\nb"] {
+            assert!(!is_valid_channel(name, "#&", 50));
+        }
+    }
+
+    #[test]
+    fn test_valid_nick() {
+        for nick in ["rick", "Rick-Astley", "[rick]", "`rick^", "r1ck_{|}\\"] {
+            assert!(is_valid_nick(nick, 30));
+        }
+    }
+
+    #[test]
+    fn test_nick_too_long() {
+        assert!(is_valid_nick("rick", 4));
+        assert!(!is_valid_nick("ricka", 4));
+    }
+
+    #[test]
+    fn test_nick_bad_start() {
+        assert!(!is_valid_nick("1rick", 30));
+        assert!(!is_valid_nick("-rick", 30));
+        assert!(!is_valid_nick("", 30));
+    }
+
+    #[test]
+    fn test_nick_illegal_chars() {
+        for nick in ["rick astley", "rick!", "rick@host", "#rick", "rické"] {
+            assert!(!is_valid_nick(nick, 30));
+        }
+    }
+
+    #[test]
+    fn test_validate_valid() {
+        let msg = "@id=123;+example.com/foo=a\\sb :nick!user@host PRIVMSG #rickastley :Never gonna give you up!\r\nPING :x\r\n";
+        assert!(validate(msg).is_empty());
+    }
+
+    #[test]
+    fn test_validate_multiple_issues() {
+        let msg = format!(
+            "@a_b=c\\x;d=\\ PRIVMSG:hi\r\n@id=1\r\nPRIVMSG #a :{}\r\nPING :a\0b\rc\r\nPING",
+            "a".repeat(500)
+        );
+        let issues: Vec<(IssueSeverity, std::ops::Range<usize>)> = validate(&msg)
+            .into_iter()
+            .map(|x| (x.severity, x.range))
+            .collect();
+
+        assert_eq!(
+            issues,
+            vec![
+                (IssueSeverity::Error, 1..4),
+                (IssueSeverity::Warning, 6..8),
+                (IssueSeverity::Warning, 11..12),
+                (IssueSeverity::Error, 20..21),
+                (IssueSeverity::Error, 25..30),
+                (IssueSeverity::Error, 32..544),
+                (IssueSeverity::Error, 553..554),
+                (IssueSeverity::Error, 555..556),
+                (IssueSeverity::Warning, 559..563),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_tags_too_long() {
+        let msg = format!("@a={} PING :x\r\n", "b".repeat(8188));
+        let issues = validate(&msg);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].range, 0..8191);
+        assert!(issues[0].message.starts_with("tag section is too long"));
+    }
+
+    #[test]
+    fn test_validate_tags_after_source() {
+        let issues = validate(":nick!user@host @id=1 PING :x\r\n");
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].range, 16..21);
+        assert_eq!(issues[0].message, "tags appear after the source");
+    }
+}